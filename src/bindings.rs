@@ -1,35 +1,183 @@
-use crate::{state::State, tui::WindowTab};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
 
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{config::Config, log, state::State, tui::WindowTab};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Binding {
     Quit,
     ToggleInstalled,
     ToggleAvailable,
     Next,
     Previous,
+    NextTab,
+    PreviousTab,
     Install,
+    Preview,
+    CancelPreview,
     Update,
     Delete,
+    Mark,
+    MarkAll,
+    Sort,
+    Filter,
+    Disable,
+    Undo,
     Search,
     ExitSearch,
     FindSearch,
     ClearSearch,
+    ScrollDown,
+    ScrollUp,
+    Add,
+    CancelAdd,
+    ConfirmAdd,
+    ShowLog,
+    HideLog,
+    ShowHelp,
+    HideHelp,
+    Confirm,
+    Cancel,
+    EditConfig,
+    NewConfig,
+    RenameConfig,
+    ConfirmName,
+    CancelName,
+    SelectTab,
 }
 
 impl Binding {
-    pub fn key(&self) -> String {
+    pub const ALL: [Self; 39] = [
+        Self::Quit,
+        Self::ToggleInstalled,
+        Self::ToggleAvailable,
+        Self::Next,
+        Self::Previous,
+        Self::NextTab,
+        Self::PreviousTab,
+        Self::Install,
+        Self::Preview,
+        Self::CancelPreview,
+        Self::Update,
+        Self::Delete,
+        Self::Mark,
+        Self::MarkAll,
+        Self::Sort,
+        Self::Filter,
+        Self::Disable,
+        Self::Undo,
+        Self::Search,
+        Self::ExitSearch,
+        Self::FindSearch,
+        Self::ClearSearch,
+        Self::ScrollDown,
+        Self::ScrollUp,
+        Self::Add,
+        Self::CancelAdd,
+        Self::ConfirmAdd,
+        Self::ShowLog,
+        Self::HideLog,
+        Self::ShowHelp,
+        Self::HideHelp,
+        Self::Confirm,
+        Self::Cancel,
+        Self::EditConfig,
+        Self::NewConfig,
+        Self::RenameConfig,
+        Self::ConfirmName,
+        Self::CancelName,
+        Self::SelectTab,
+    ];
+
+    // The action name used by `@tmuxedo-key-<name>` in tmuxedo.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::ToggleInstalled | Self::ToggleAvailable => "toggle-list",
+            Self::Next => "next",
+            Self::Previous => "previous",
+            Self::NextTab => "next-tab",
+            Self::PreviousTab => "previous-tab",
+            Self::Install => "install",
+            Self::Preview => "preview",
+            Self::CancelPreview => "cancel-preview",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Mark => "mark",
+            Self::MarkAll => "mark-all",
+            Self::Sort => "cycle-sort",
+            Self::Filter => "cycle-filter",
+            Self::Disable => "toggle-disabled",
+            Self::Undo => "undo",
+            Self::Search => "search",
+            Self::ExitSearch => "exit-search",
+            Self::FindSearch => "confirm-search",
+            Self::ClearSearch => "clear-search",
+            Self::ScrollDown => "scroll-down",
+            Self::ScrollUp => "scroll-up",
+            Self::Add => "add",
+            Self::CancelAdd => "cancel-add",
+            Self::ConfirmAdd => "confirm-add",
+            Self::ShowLog => "show-log",
+            Self::HideLog => "hide-log",
+            Self::ShowHelp => "show-help",
+            Self::HideHelp => "hide-help",
+            Self::Confirm => "confirm",
+            Self::Cancel => "cancel",
+            Self::EditConfig => "edit-config",
+            Self::NewConfig => "new-config",
+            Self::RenameConfig => "rename-config",
+            Self::ConfirmName => "confirm-name",
+            Self::CancelName => "cancel-name",
+            Self::SelectTab => "select-tab",
+        }
+    }
+
+    pub fn default_keys(&self) -> &'static str {
         match self {
-            Self::Quit => String::from("q"),
-            Self::ToggleInstalled => String::from("C-o"),
-            Self::ToggleAvailable => String::from("C-o"),
-            Self::Next => String::from("j"),
-            Self::Previous => String::from("k"),
-            Self::Install => String::from("I"),
-            Self::Update => String::from("U"),
-            Self::Delete => String::from("X"),
-            Self::Search => String::from("/"),
-            Self::ExitSearch => String::from("esc"),
-            Self::FindSearch => String::from("enter"),
-            Self::ClearSearch => String::from("esc"),
+            Self::Quit => "q",
+            Self::ToggleInstalled => "C-o",
+            Self::ToggleAvailable => "C-o",
+            Self::Next => "j down",
+            Self::Previous => "k up",
+            Self::NextTab => "tab",
+            Self::PreviousTab => "S-tab",
+            Self::Install => "I",
+            Self::Preview => "p",
+            Self::CancelPreview => "esc",
+            Self::Update => "U",
+            Self::Delete => "X",
+            Self::Mark => "space",
+            Self::MarkAll => "C-a",
+            Self::Sort => "s",
+            Self::Filter => "f",
+            Self::Disable => "D",
+            Self::Undo => "u",
+            Self::Search => "/",
+            Self::ExitSearch => "esc",
+            Self::FindSearch => "enter",
+            Self::ClearSearch => "esc",
+            Self::ScrollDown => "J",
+            Self::ScrollUp => "K",
+            Self::Add => "A",
+            Self::CancelAdd => "esc",
+            Self::ConfirmAdd => "enter",
+            Self::ShowLog => "L",
+            Self::HideLog => "esc",
+            Self::ShowHelp => "?",
+            Self::HideHelp => "esc ?",
+            Self::Confirm => "y enter",
+            Self::Cancel => "n esc",
+            Self::EditConfig => "e enter",
+            Self::NewConfig => "n",
+            Self::RenameConfig => "r",
+            Self::ConfirmName => "enter",
+            Self::CancelName => "esc",
+            Self::SelectTab => "1-9",
         }
     }
 
@@ -40,43 +188,332 @@ impl Binding {
             Self::ToggleAvailable => String::from("toggle available"),
             Self::Next => String::from("next"),
             Self::Previous => String::from("previous"),
+            Self::NextTab => String::from("next tab"),
+            Self::PreviousTab => String::from("previous tab"),
             Self::Install => String::from("install"),
+            Self::Preview => String::from("preview theme"),
+            Self::CancelPreview => String::from("revert preview"),
             Self::Update => String::from("update"),
             Self::Delete => String::from("delete"),
+            Self::Mark => String::from("mark"),
+            Self::MarkAll => String::from("mark all"),
+            Self::Sort => String::from("sort"),
+            Self::Filter => String::from("filter"),
+            Self::Disable => String::from("disable/enable"),
+            Self::Undo => String::from("undo"),
             Self::Search => String::from("search"),
             Self::ExitSearch => String::from("exit search"),
             Self::FindSearch => String::from("confirm"),
             Self::ClearSearch => String::from("clear search"),
+            Self::ScrollDown => String::from("scroll down"),
+            Self::ScrollUp => String::from("scroll up"),
+            Self::Add => String::from("add plugin"),
+            Self::CancelAdd => String::from("cancel"),
+            Self::ConfirmAdd => String::from("add"),
+            Self::ShowLog => String::from("log"),
+            Self::HideLog => String::from("close log"),
+            Self::ShowHelp => String::from("help"),
+            Self::HideHelp => String::from("close help"),
+            Self::Confirm => String::from("yes"),
+            Self::Cancel => String::from("no"),
+            Self::EditConfig => String::from("edit"),
+            Self::NewConfig => String::from("new file"),
+            Self::RenameConfig => String::from("rename"),
+            Self::ConfirmName => String::from("save"),
+            Self::CancelName => String::from("cancel"),
+            Self::SelectTab => String::from("select tab by number"),
+        }
+    }
+
+    // Tabs are picked with the digit keys, which can't be rebound.
+    fn is_fixed(&self) -> bool {
+        matches!(self, Self::SelectTab)
+    }
+}
+
+pub const HELP: [(&str, &[Binding]); 11] = [
+    (
+        "General",
+        &[
+            Binding::Quit,
+            Binding::ShowHelp,
+            Binding::HideHelp,
+            Binding::Undo,
+        ],
+    ),
+    (
+        "Tabs",
+        &[Binding::SelectTab, Binding::NextTab, Binding::PreviousTab],
+    ),
+    (
+        "Lists",
+        &[
+            Binding::Next,
+            Binding::Previous,
+            Binding::ToggleInstalled,
+            Binding::ToggleAvailable,
+            Binding::Mark,
+            Binding::MarkAll,
+            Binding::Sort,
+            Binding::Filter,
+        ],
+    ),
+    (
+        "Installed plugins",
+        &[Binding::Update, Binding::Disable, Binding::Delete],
+    ),
+    (
+        "Available plugins",
+        &[Binding::Install, Binding::Preview, Binding::CancelPreview],
+    ),
+    (
+        "Search",
+        &[
+            Binding::Search,
+            Binding::FindSearch,
+            Binding::ExitSearch,
+            Binding::ClearSearch,
+        ],
+    ),
+    (
+        "Add plugin",
+        &[Binding::Add, Binding::ConfirmAdd, Binding::CancelAdd],
+    ),
+    ("README", &[Binding::ScrollDown, Binding::ScrollUp]),
+    (
+        "Config files",
+        &[
+            Binding::EditConfig,
+            Binding::NewConfig,
+            Binding::RenameConfig,
+            Binding::ConfirmName,
+            Binding::CancelName,
+        ],
+    ),
+    ("Log", &[Binding::ShowLog, Binding::HideLog]),
+    ("Confirmation", &[Binding::Confirm, Binding::Cancel]),
+];
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeySpec {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    // Keys are written the way tmux writes them: `j`, `C-o`, `M-x`, `S-tab`,
+    // `enter`, `esc`, `up`, ... Shift is implied by upper-case characters.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        'prefixes: loop {
+            for (prefix, modifier) in [
+                ("C-", KeyModifiers::CONTROL),
+                ("M-", KeyModifiers::ALT),
+                ("S-", KeyModifiers::SHIFT),
+            ] {
+                if let Some(key) = rest.strip_prefix(prefix)
+                    && !key.is_empty()
+                {
+                    modifiers |= modifier;
+                    rest = key;
+                    continue 'prefixes;
+                }
+            }
+            break;
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "btab" | "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "bspace" | "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "ppage" => KeyCode::PageUp,
+            "pgdn" | "npage" => KeyCode::PageDown,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    // `S-x` means the upper-case character, which is what
+                    // terminals send for it.
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        let mut upper = c.to_uppercase();
+                        match (upper.next(), upper.next()) {
+                            (Some(upper), None) => KeyCode::Char(upper),
+                            _ => return None,
+                        }
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Self {
+            code,
+            modifiers: modifiers - KeyModifiers::SHIFT,
+        })
+    }
+
+    // Terminals disagree on whether upper-case characters and back-tab carry
+    // the shift modifier, so it is ignored on both sides.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.modifiers == key.modifiers - KeyModifiers::SHIFT
+    }
+}
+
+impl Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "C-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "M-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "S-tab"),
+            KeyCode::Backspace => write!(f, "bspace"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pgup"),
+            KeyCode::PageDown => write!(f, "pgdn"),
+            _ => write!(f, "?"),
         }
     }
 }
 
+fn parse_keys(specs: &str) -> Option<Vec<KeySpec>> {
+    specs.split_whitespace().map(KeySpec::parse).collect()
+}
+
+pub struct Keymap {
+    keys: HashMap<&'static str, Vec<KeySpec>>,
+}
+
+impl Keymap {
+    pub fn load() -> Self {
+        let config = Config::load();
+        let mut keys = HashMap::new();
+        for binding in Binding::ALL.iter().filter(|b| !b.is_fixed()) {
+            let option = format!("key-{}", binding.name());
+            let specs = match config.get(&option).map(parse_keys) {
+                Some(Some(specs)) => specs,
+                Some(None) => {
+                    let message = format!(
+                        "@tmuxedo-{option}: invalid key in '{}', using the default",
+                        config.get(&option).unwrap_or_default()
+                    );
+                    log::report(&message);
+                    log::error(&message);
+                    parse_keys(binding.default_keys()).unwrap_or_default()
+                }
+                None => parse_keys(binding.default_keys()).unwrap_or_default(),
+            };
+            keys.insert(binding.name(), specs);
+        }
+        Self { keys }
+    }
+
+    pub fn keys(&self, binding: Binding) -> &[KeySpec] {
+        self.keys
+            .get(binding.name())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn matches(&self, binding: Binding, key: &KeyEvent) -> bool {
+        self.keys(binding).iter().any(|spec| spec.matches(key))
+    }
+
+    pub fn repr(&self, binding: Binding) -> String {
+        if binding.is_fixed() {
+            return binding.default_keys().to_string();
+        }
+        self.keys(binding)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
 pub fn get(state: &State) -> Vec<Binding> {
+    if state.show_help {
+        return vec![
+            Binding::Quit,
+            Binding::HideHelp,
+            Binding::ScrollDown,
+            Binding::ScrollUp,
+        ];
+    }
+    if state.pending_action.is_some() {
+        return vec![Binding::Confirm, Binding::Cancel];
+    }
+    if state.show_log {
+        return vec![Binding::Quit, Binding::HideLog];
+    }
+    if state.add_mode {
+        return vec![Binding::CancelAdd, Binding::ConfirmAdd];
+    }
+    if state.name_input.is_some() {
+        return vec![Binding::CancelName, Binding::ConfirmName];
+    }
     match state.search_mode {
         true => vec![Binding::ExitSearch, Binding::FindSearch],
         false => {
-            if state.tab == WindowTab::All {
-                let mut bindings = vec![
+            if state.tab == WindowTab::Config {
+                vec![
                     Binding::Quit,
+                    Binding::ShowHelp,
                     Binding::Next,
                     Binding::Previous,
-                    Binding::Search,
-                    Binding::Update,
-                    Binding::Delete,
-                ];
-                if !state.search_string.is_empty() {
-                    bindings.push(Binding::ClearSearch);
-                }
-                bindings
+                    Binding::EditConfig,
+                    Binding::NewConfig,
+                    Binding::RenameConfig,
+                    Binding::ScrollDown,
+                    Binding::ScrollUp,
+                    Binding::ShowLog,
+                ]
             } else if state.toggle_available_list {
                 let mut bindings = vec![
                     Binding::Quit,
+                    Binding::ShowHelp,
                     Binding::Next,
                     Binding::Previous,
                     Binding::Search,
                     Binding::ToggleInstalled,
+                    Binding::Mark,
+                    Binding::Sort,
+                    Binding::Filter,
                     Binding::Install,
+                    Binding::Add,
+                    Binding::ShowLog,
                 ];
+                if !state.undo_stack.is_empty() {
+                    bindings.push(Binding::Undo);
+                }
+                if state.can_preview() {
+                    bindings.push(Binding::Preview);
+                }
+                if state.preview.is_some() {
+                    bindings.push(Binding::CancelPreview);
+                }
                 if !state.search_string.is_empty() {
                     bindings.push(Binding::ClearSearch);
                 }
@@ -84,13 +521,23 @@ pub fn get(state: &State) -> Vec<Binding> {
             } else {
                 let mut bindings = vec![
                     Binding::Quit,
+                    Binding::ShowHelp,
                     Binding::Next,
                     Binding::Previous,
                     Binding::Search,
                     Binding::ToggleAvailable,
+                    Binding::Mark,
+                    Binding::Sort,
+                    Binding::Filter,
                     Binding::Update,
+                    Binding::Disable,
                     Binding::Delete,
+                    Binding::Add,
+                    Binding::ShowLog,
                 ];
+                if !state.undo_stack.is_empty() {
+                    bindings.push(Binding::Undo);
+                }
                 if !state.search_string.is_empty() {
                     bindings.push(Binding::ClearSearch);
                 }
@@ -99,3 +546,100 @@ pub fn get(state: &State) -> Vec<Binding> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn parse(spec: &str) -> KeySpec {
+        KeySpec::parse(spec).unwrap_or_else(|| panic!("{spec} should parse"))
+    }
+
+    #[test]
+    fn parse_reads_plain_characters() {
+        let spec = parse("j");
+
+        assert!(spec.matches(&key(KeyCode::Char('j'), KeyModifiers::NONE)));
+        assert!(!spec.matches(&key(KeyCode::Char('j'), KeyModifiers::CONTROL)));
+        assert_eq!(spec.to_string(), "j");
+    }
+
+    #[test]
+    fn parse_reads_modifiers() {
+        let control = parse("C-o");
+        let alt = parse("M-x");
+        let both = parse("C-M-x");
+
+        assert!(control.matches(&key(KeyCode::Char('o'), KeyModifiers::CONTROL)));
+        assert!(alt.matches(&key(KeyCode::Char('x'), KeyModifiers::ALT)));
+        assert!(both.matches(&key(
+            KeyCode::Char('x'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT
+        )));
+        assert_eq!(both.to_string(), "C-M-x");
+    }
+
+    #[test]
+    fn parse_reads_shift_as_upper_case() {
+        let spec = parse("S-x");
+
+        assert!(spec.matches(&key(KeyCode::Char('X'), KeyModifiers::SHIFT)));
+        assert!(spec.matches(&key(KeyCode::Char('X'), KeyModifiers::NONE)));
+        assert!(!spec.matches(&key(KeyCode::Char('x'), KeyModifiers::NONE)));
+        assert_eq!(spec.to_string(), parse("X").to_string());
+    }
+
+    #[test]
+    fn parse_reads_shift_tab_as_back_tab() {
+        let spec = parse("S-tab");
+
+        assert!(spec.matches(&key(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(spec.matches(&key(KeyCode::BackTab, KeyModifiers::NONE)));
+        assert!(!spec.matches(&key(KeyCode::Tab, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn parse_reads_named_keys() {
+        let named = [
+            ("enter", KeyCode::Enter),
+            ("esc", KeyCode::Esc),
+            ("tab", KeyCode::Tab),
+            ("space", KeyCode::Char(' ')),
+            ("bspace", KeyCode::Backspace),
+            ("up", KeyCode::Up),
+            ("down", KeyCode::Down),
+            ("PgUp", KeyCode::PageUp),
+            ("npage", KeyCode::PageDown),
+        ];
+
+        for (spec, code) in named {
+            assert!(
+                parse(spec).matches(&key(code, KeyModifiers::NONE)),
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_reads_multi_byte_characters() {
+        assert!(parse("€").matches(&key(KeyCode::Char('€'), KeyModifiers::NONE)));
+        assert!(parse("C-€").matches(&key(KeyCode::Char('€'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_specs() {
+        for spec in ["", "jk", "C-", "C-jk", "X-j", "S-ß", "pagedown"] {
+            assert!(KeySpec::parse(spec).is_none(), "{spec} should not parse");
+        }
+    }
+
+    #[test]
+    fn parse_keys_rejects_a_list_with_one_invalid_key() {
+        assert_eq!(parse_keys("j down C-n").map(|keys| keys.len()), Some(3));
+        assert!(parse_keys("j nope").is_none());
+    }
+}
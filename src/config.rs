@@ -0,0 +1,71 @@
+use std::{collections::HashMap, fs, time::Duration};
+
+use crate::tmuxedo::Path;
+
+const OPTION_PREFIX: &str = "@tmuxedo-";
+const DEFAULT_PLUGIN_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_STALE_AFTER_DAYS: u64 = 365;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Options live in tmuxedo.conf as tmux user options, e.g.
+// `set -g @tmuxedo-plugin-timeout 5`, so tmux still accepts the file when sourcing it.
+pub struct Config {
+    options: HashMap<String, String>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let content = fs::read_to_string(Path::TmuxedoConfig.get()).unwrap_or_default();
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Self {
+        let options = content.lines().filter_map(parse_option_line).collect();
+        Self { options }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    pub fn plugin_timeout(&self) -> Duration {
+        let seconds = self
+            .get("plugin-timeout")
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_PLUGIN_TIMEOUT_SECS);
+        Duration::from_secs(seconds)
+    }
+
+    pub fn confirm(&self) -> bool {
+        !matches!(self.get("confirm"), Some("off" | "no" | "false" | "0"))
+    }
+
+    pub fn stale_after(&self) -> Duration {
+        let days = self
+            .get("stale-after")
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_STALE_AFTER_DAYS);
+        Duration::from_secs(days * SECS_PER_DAY)
+    }
+}
+
+fn parse_option_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    let mut tokens = line.split_whitespace();
+    if !matches!(tokens.next(), Some("set" | "set-option")) {
+        return None;
+    }
+
+    let name = tokens.find(|token| !token.starts_with('-'))?;
+    let key = name.strip_prefix(OPTION_PREFIX)?;
+
+    let start = line.find(name)? + name.len();
+    let value = line[start..].trim();
+    let value = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+        .unwrap_or(value);
+
+    Some((key.to_string(), value.to_string()))
+}
@@ -0,0 +1,295 @@
+use std::{collections::HashSet, fs, process::Command};
+
+use regex::Regex;
+
+use crate::{
+    config::Config,
+    plugins::{ManifestEntry, read_manifest},
+    register::Registry,
+    registry_cache::RegistryCache,
+    tmuxedo::{ConfigError, Path, check_config_files, config_files},
+    utils::format_plugin_dir_name,
+};
+
+const MIN_TMUX_VERSION: (u32, u32) = (3, 2);
+const MIN_GIT_VERSION: (u32, u32) = (2, 0);
+const MIN_CURL_VERSION: (u32, u32) = (7, 55);
+
+pub struct Check {
+    pub summary: String,
+    pub fix: Option<String>,
+    pub warning: bool,
+}
+
+impl Check {
+    fn pass(summary: String) -> Self {
+        Self {
+            summary,
+            fix: None,
+            warning: false,
+        }
+    }
+
+    fn fail(summary: String, fix: String) -> Self {
+        Self {
+            summary,
+            fix: Some(fix),
+            warning: false,
+        }
+    }
+
+    fn warn(summary: String, fix: String) -> Self {
+        Self {
+            summary,
+            fix: Some(fix),
+            warning: true,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.fix.is_none() || self.warning
+    }
+}
+
+fn parse_version(text: &str) -> Option<(u32, u32)> {
+    let re = Regex::new(r"(\d+)\.(\d+)").unwrap();
+    let caps = re.captures(text)?;
+    Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+}
+
+fn check_tool(name: &str, version_arg: &str, minimum: (u32, u32)) -> Check {
+    let output = match Command::new(name).arg(version_arg).output() {
+        Ok(output) => output,
+        Err(_) => {
+            return Check::fail(
+                format!("{name} not found on PATH"),
+                format!("Install {name} {}.{} or newer", minimum.0, minimum.1),
+            );
+        }
+    };
+
+    // Some tools (curl) list their libraries after the version, so only the
+    // words up to the version number are kept.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut words = Vec::new();
+    for word in stdout.split_whitespace() {
+        words.push(word);
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+    }
+    let text = words.join(" ");
+    match parse_version(&text) {
+        Some(version) if version >= minimum => Check::pass(format!("{text} found")),
+        Some(_) => Check::fail(
+            format!("{text} is older than {}.{}", minimum.0, minimum.1),
+            format!("Upgrade {name} to {}.{} or newer", minimum.0, minimum.1),
+        ),
+        None => Check::fail(
+            format!("Could not determine the {name} version from \"{text}\""),
+            format!("Make sure `{name} {version_arg}` works"),
+        ),
+    }
+}
+
+// Only `tmuxedo registry refresh` needs curl, to ask GitHub whether plugins
+// are archived, so a missing one is a warning.
+fn check_curl() -> Check {
+    let mut check = check_tool("curl", "--version", MIN_CURL_VERSION);
+    if check.fix.is_some() {
+        check.summary = format!(
+            "{}; `tmuxedo registry refresh` cannot tell archived plugins",
+            check.summary
+        );
+        check.warning = true;
+    }
+    check
+}
+
+fn check_tmux_hook() -> Check {
+    let path = Path::TmuxConfig.get();
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let hooked = content.lines().map(str::trim).any(|line| {
+        !line.starts_with('#') && line.contains("run-shell") && line.contains("tmuxedo")
+    });
+
+    match hooked {
+        true => Check::pass(format!("{} runs tmuxedo", path.display())),
+        false => Check::fail(
+            format!("{} does not run tmuxedo", path.display()),
+            format!("Add `run-shell 'tmuxedo'` to the end of {}", path.display()),
+        ),
+    }
+}
+
+fn git_output(dir: &std::path::Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    match output.status.success() {
+        true => Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => None,
+    }
+}
+
+fn check_plugin(entry: &ManifestEntry) -> Check {
+    let mut dir = Path::Plugins.get();
+    dir.push(format_plugin_dir_name(&entry.repo));
+
+    if !dir.exists() {
+        return Check::fail(
+            format!("{} is not cloned", entry.repo),
+            String::from("Run `tmuxedo` to clone missing plugins"),
+        );
+    }
+
+    let toplevel = git_output(&dir, &["rev-parse", "--show-toplevel"]);
+    let is_repo = toplevel
+        .and_then(|t| fs::canonicalize(t).ok())
+        .zip(fs::canonicalize(&dir).ok())
+        .is_some_and(|(toplevel, dir)| toplevel == dir);
+    if !is_repo {
+        return Check::fail(
+            format!("{} is not a valid git repository", dir.display()),
+            format!(
+                "Remove {} and run `tmuxedo` to clone it again",
+                dir.display()
+            ),
+        );
+    }
+
+    let branch = git_output(&dir, &["branch", "--show-current"])
+        .filter(|branch| !branch.is_empty())
+        .unwrap_or_else(|| String::from("a detached HEAD"));
+    let expected = entry.branch.as_ref().or_else(|| {
+        Registry::get()
+            .find(&entry.repo)
+            .and_then(|e| e.branch.as_ref())
+    });
+    match expected {
+        Some(expected) if *expected != branch => Check::fail(
+            format!("{} is on {branch}, expected {expected}", entry.repo),
+            format!("Run `git -C {} checkout {expected}`", dir.display()),
+        ),
+        _ => Check::pass(format!("{} is cloned on {branch}", entry.repo)),
+    }
+}
+
+fn check_stale(manifest: &[ManifestEntry]) -> Vec<Check> {
+    let cache = RegistryCache::load();
+    let stale_after = Config::load().stale_after();
+    manifest
+        .iter()
+        .filter_map(|entry| {
+            let staleness = cache.staleness(&entry.repo, stale_after)?;
+            Some(Check::warn(
+                format!("{} looks abandoned: {staleness}", entry.repo),
+                format!(
+                    "Look for a maintained fork or alternative, or remove {} from plugins.conf",
+                    entry.repo
+                ),
+            ))
+        })
+        .collect()
+}
+
+fn check_orphans(manifest: &[ManifestEntry]) -> Vec<Check> {
+    let expected: HashSet<String> = manifest
+        .iter()
+        .map(|entry| format_plugin_dir_name(&entry.repo))
+        .collect();
+
+    let entries = match fs::read_dir(Path::Plugins.get()) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter(|entry| !expected.contains(&entry.file_name().to_string_lossy().to_string()))
+        .map(|entry| {
+            Check::fail(
+                format!("{} is not in plugins.conf", entry.path().display()),
+                format!(
+                    "Add it to plugins.conf or remove it with `rm -rf {}`",
+                    entry.path().display()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn check_config(path: &std::path::Path, errors: Option<Vec<ConfigError>>) -> Check {
+    match errors {
+        None => Check::pass(format!("{} parses", path.display())),
+        Some(errors) => Check::fail(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n    "),
+            format!("Fix {} and run `tmuxedo` again", path.display()),
+        ),
+    }
+}
+
+fn checks() -> Vec<Check> {
+    let mut checks = vec![
+        check_tool("tmux", "-V", MIN_TMUX_VERSION),
+        check_tool("git", "--version", MIN_GIT_VERSION),
+        check_curl(),
+        check_tmux_hook(),
+    ];
+
+    match read_manifest() {
+        Ok(manifest) => {
+            checks.extend(manifest.iter().map(check_plugin));
+            checks.extend(check_stale(&manifest));
+            checks.extend(check_orphans(&manifest));
+        }
+        Err(e) => checks.push(Check::fail(
+            format!("Could not read plugins.conf: {e}"),
+            String::from("Run `tmuxedo` to create it"),
+        )),
+    }
+
+    let files = config_files(&Path::Tmuxedo.get());
+    let mut errors = check_config_files(&files);
+    checks.extend(
+        files
+            .iter()
+            .map(|path| check_config(path, errors.remove(path))),
+    );
+    checks
+}
+
+pub fn run_doctor() -> bool {
+    let checks = checks();
+    for check in &checks {
+        match &check.fix {
+            None => println!("  ✔ {}", check.summary),
+            Some(fix) if check.warning => {
+                println!("  ⚠ {}", check.summary);
+                println!("      fix: {fix}");
+            }
+            Some(fix) => {
+                println!("  ✘ {}", check.summary);
+                println!("      fix: {fix}");
+            }
+        }
+    }
+
+    let failures = checks.iter().filter(|c| !c.passed()).count();
+    let warnings = checks.iter().filter(|c| c.warning).count();
+    println!();
+    match (failures, warnings) {
+        (0, 0) => println!("Everything looks good!"),
+        (0, w) => println!("{w} warning(s)"),
+        (n, 0) => println!("{n} problem(s) found"),
+        (n, w) => println!("{n} problem(s) found, {w} warning(s)"),
+    }
+    failures == 0
+}
@@ -0,0 +1,133 @@
+use std::{
+    fmt::{self, Display},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::{tmuxedo::Path, utils::format_timestamp};
+
+const MAX_LOG_SIZE: u64 = 512 * 1024;
+const MAX_ROTATED_LOGS: usize = 3;
+
+// While the TUI owns the terminal, diagnostics are queued for its notification
+// area instead of being written over the alternate screen.
+static CAPTURED: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+pub enum Level {
+    Info,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "INFO "),
+            Self::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+pub fn info(message: &str) {
+    let _ = write(Level::Info, message);
+}
+
+pub fn error(message: &str) {
+    let _ = write(Level::Error, message);
+}
+
+pub fn report(message: &str) {
+    if let Ok(mut captured) = CAPTURED.lock()
+        && let Some(queue) = captured.as_mut()
+    {
+        queue.push(message.to_string());
+        return;
+    }
+    eprintln!("{message}");
+}
+
+pub fn start_capture() {
+    if let Ok(mut captured) = CAPTURED.lock() {
+        *captured = Some(vec![]);
+    }
+}
+
+pub fn stop_capture() -> Vec<String> {
+    CAPTURED
+        .lock()
+        .ok()
+        .and_then(|mut captured| captured.take())
+        .unwrap_or_default()
+}
+
+pub fn take_captured() -> Vec<String> {
+    CAPTURED
+        .lock()
+        .ok()
+        .and_then(|mut captured| captured.as_mut().map(std::mem::take))
+        .unwrap_or_default()
+}
+
+fn write(level: Level, message: &str) -> io::Result<()> {
+    let path = Path::Log.get();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    rotate(&path)?;
+
+    let message = message.trim().replace('\n', " | ");
+    let timestamp = format_timestamp(SystemTime::now());
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{timestamp} {level} {message}")
+}
+
+fn rotated_path(path: &std::path::Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{index}"));
+    PathBuf::from(rotated)
+}
+
+fn rotate(path: &std::path::Path) -> io::Result<()> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.len() >= MAX_LOG_SIZE => {}
+        _ => return Ok(()),
+    }
+
+    for index in (1..MAX_ROTATED_LOGS).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            fs::rename(from, rotated_path(path, index + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))
+}
+
+pub fn recent(count: usize) -> Vec<String> {
+    let path = Path::Log.get();
+    let mut lines: Vec<String> = vec![];
+
+    for index in 0..=MAX_ROTATED_LOGS {
+        if lines.len() >= count {
+            break;
+        }
+        let file = match index {
+            0 => path.clone(),
+            _ => rotated_path(&path, index),
+        };
+        let content = fs::read_to_string(file).unwrap_or_default();
+        let mut older: Vec<String> = content.lines().map(String::from).collect();
+        older.append(&mut lines);
+        lines = older;
+    }
+
+    let skip = lines.len().saturating_sub(count);
+    lines.split_off(skip)
+}
+
+pub fn print_recent(count: usize) {
+    for line in recent(count) {
+        println!("{line}");
+    }
+}
@@ -1,6 +1,11 @@
-use std::{error::Error, io, process::Command};
+use std::{
+    error::Error,
+    io,
+    path::PathBuf,
+    process::{self, Command, Stdio},
+};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -9,14 +14,25 @@ use crossterm::{
 use ratatui::{Terminal, prelude::CrosstermBackend};
 
 use crate::{
+    doctor::run_doctor,
     plugins::run_plugins,
-    tmuxedo::{ensure_structure, source_all_tmuxedo_files},
+    profile::profile_startup,
+    register::sync_registries,
+    tmuxedo::{ensure_structure, run_check, source_all_tmuxedo_files},
     tui::run_tmuxedo_tui,
 };
 
 mod bindings;
+mod config;
+mod doctor;
+mod log;
 mod plugins;
+mod preview;
+mod profile;
+mod readme;
 mod register;
+mod registry_cache;
+mod sort;
 mod state;
 mod tmuxedo;
 mod tui;
@@ -24,24 +40,60 @@ mod utils;
 
 pub enum TmuxCommand {
     SourceFile,
-    RunShell,
 }
 
 impl TmuxCommand {
     fn command(&self) -> String {
         match self {
             Self::SourceFile => String::from("source-file"),
-            Self::RunShell => String::from("run-shell"),
         }
     }
 
-    pub fn run(&self, args: Vec<String>) {
-        let status = Command::new("tmux").arg(self.command()).args(args).status();
-
-        match status {
-            Ok(status) if status.success() => {}
-            Ok(status) => eprintln!("Tmux command failed with status: {status}"),
-            Err(e) => eprintln!("Failed to run tmux command: {e}"),
+    // tmux's stderr is captured so that its errors reach the TUI's
+    // notifications instead of being written over the screen. tmux only names
+    // the file for parse errors, so the arguments (the file, for source-file)
+    // go in front of the others.
+    pub fn run(&self, args: Vec<String>) -> bool {
+        let command = format!("{} {}", self.command(), args.join(" "));
+        let target = args.join(" ");
+        let output = Command::new("tmux")
+            .arg(self.command())
+            .args(&args)
+            .stdin(Stdio::null())
+            .output();
+
+        match output {
+            Ok(output) if output.status.success() => {
+                log::info(&format!("{command}: ok"));
+                true
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let errors: Vec<&str> = stderr
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                if errors.is_empty() {
+                    let status = output.status;
+                    log::report(&format!("{target}: tmux exited with {status}"));
+                    log::error(&format!("{command}: failed with status {status}"));
+                }
+                for error in errors {
+                    let message = match args.iter().any(|arg| error.starts_with(arg.as_str())) {
+                        true => error.to_string(),
+                        false => format!("{target}: {error}"),
+                    };
+                    log::report(&message);
+                    log::error(&format!("{command}: {error}"));
+                }
+                false
+            }
+            Err(e) => {
+                log::report(&format!("Failed to run tmux command: {e}"));
+                log::error(&format!("{command}: failed to run tmux: {e}"));
+                false
+            }
         }
     }
 }
@@ -54,12 +106,48 @@ struct Cli {
 
     #[arg(short, long, default_value_t = false)]
     update: bool,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Time every source-file and plugin run-shell and print them slowest first
+    ProfileStartup {
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Check tmuxedo's dependencies, plugins and config files for problems
+    Doctor,
+    /// Validate config files with tmux's parse-only mode without sourcing them
+    Check {
+        /// Directory to check instead of ~/.config/tmux/tmuxedo
+        dir: Option<PathBuf>,
+    },
+    /// Manage the plugin registry
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommands,
+    },
+    /// Print the most recent entries from the tmuxedo log
+    Log {
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RegistryCommands {
+    /// Fetch the latest metadata for every registry entry into the local cache
+    Refresh,
 }
 
 async fn run_app(cli: &Cli) -> Result<(), Box<dyn Error>> {
     ensure_structure();
+    sync_registries(cli.update).await;
     source_all_tmuxedo_files(cli.update).await;
-    run_plugins();
+    run_plugins().await;
 
     Ok(())
 }
@@ -71,7 +159,9 @@ async fn run_tui() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    log::start_capture();
     let res = run_tmuxedo_tui(&mut terminal).await;
+    let reports = log::stop_capture();
 
     disable_raw_mode()?;
     execute!(
@@ -81,6 +171,9 @@ async fn run_tui() -> Result<(), Box<dyn Error>> {
     )?;
     terminal.show_cursor()?;
 
+    for report in reports {
+        log::report(&report);
+    }
     if let Err(err) = res {
         println!("{err:?}");
     }
@@ -92,8 +185,34 @@ async fn run_tui() -> Result<(), Box<dyn Error>> {
 async fn main() {
     let cli = Cli::parse();
 
-    let _ = match cli.tui {
-        true => run_tui().await,
-        false => run_app(&cli).await,
+    let _ = match (&cli.command, cli.tui) {
+        (Some(Commands::ProfileStartup { json }), _) => {
+            profile_startup(cli.update, *json).await;
+            Ok(())
+        }
+        (Some(Commands::Doctor), _) => {
+            if !run_doctor() {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        (Some(Commands::Check { dir }), _) => {
+            if !run_check(dir.clone()) {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        (Some(Commands::Registry { command }), _) => match command {
+            RegistryCommands::Refresh => {
+                registry_cache::refresh().await;
+                Ok(())
+            }
+        },
+        (Some(Commands::Log { lines }), _) => {
+            log::print_recent(*lines);
+            Ok(())
+        }
+        (None, true) => run_tui().await,
+        (None, false) => run_app(&cli).await,
     };
 }
@@ -4,23 +4,42 @@ use std::{
     hash::{Hash, Hasher},
     io::{BufRead, BufReader},
     process::{ExitStatus, Stdio},
+    time::{Duration, Instant},
     vec,
 };
 
 use regex::Regex;
-use tokio::{io, process::Command, task};
+use tokio::{io, process::Command, task, time};
 use walkdir::WalkDir;
 
-use crate::{TmuxCommand, tmuxedo::Path, utils::format_plugin_dir_name};
+use crate::{
+    config::Config,
+    log,
+    register::Registry,
+    tmuxedo::Path,
+    utils::{format_plugin_dir_name, is_git_url},
+};
 
 #[derive(Debug, Eq, Clone)]
 pub struct Plugin {
     pub path: String,
+    pub branch: Option<String>,
+    pub disabled: bool,
     pub commit_hash: String,
     pub is_up_to_date: bool,
 }
 
 impl Plugin {
+    pub fn new(path: String, branch: Option<String>) -> Self {
+        Self {
+            path,
+            branch,
+            disabled: false,
+            commit_hash: String::new(),
+            is_up_to_date: true,
+        }
+    }
+
     pub fn set_commit_hash(&mut self, commit_hash: String) {
         self.commit_hash = commit_hash;
     }
@@ -45,43 +64,93 @@ impl Display for Plugin {
     }
 }
 
+pub fn clone_url(plugin: &str) -> String {
+    match Registry::get().find(plugin) {
+        Some(entry) => entry.url.clone(),
+        None if is_git_url(plugin) => plugin.to_string(),
+        None => format!("https://git::@github.com/{plugin}"),
+    }
+}
+
+// Checks that `plugin` is an `owner/repo` or a git URL whose remote (and
+// branch, if given) exists, before anything is cloned or written to plugins.conf.
+pub async fn validate_plugin(plugin: &str, branch: Option<&str>) -> Result<(), String> {
+    let re = Regex::new(r"^[\w.-]+/[\w.-]+$").unwrap();
+    if !re.is_match(plugin) && !is_git_url(plugin) {
+        return Err(format!("{plugin} is not an owner/repo or a git URL"));
+    }
+
+    let mut command = Command::new("git");
+    command
+        .args(["ls-remote", "--heads", &clone_url(plugin)])
+        .args(branch)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    match (output.status.success(), branch) {
+        (false, _) => Err(format!("Could not reach {plugin}")),
+        (true, Some(branch)) if output.stdout.is_empty() => {
+            Err(format!("{plugin} has no branch {branch}"))
+        }
+        _ => Ok(()),
+    }
+}
+
+// git explains failures on a "fatal:" or "error:" line, after progress output
+// such as "Cloning into ...", or otherwise in its first line of advice.
+pub fn git_error(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines
+        .iter()
+        .find(|line| line.starts_with("fatal:") || line.starts_with("error:"))
+        .or(lines.first())
+        .map(|line| line.to_string())
+        .unwrap_or_default()
+}
+
 pub async fn git_clone(plugin: &String, branch: Option<String>) -> io::Result<ExitStatus> {
     let path = Path::Plugins.get();
     let dir_name = format_plugin_dir_name(plugin);
+    let url = clone_url(plugin);
 
-    let status = match branch {
-        Some(b) => {
-            Command::new("git")
-                .arg("clone")
-                .arg("-b")
-                .arg(b)
-                .arg("--single-branch")
-                .arg("--recursive")
-                .arg(format!("https://git::@github.com/{plugin}"))
-                .arg(dir_name)
-                .current_dir(path)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .status()
-                .await?
-        }
-        None => {
-            Command::new("git")
-                .arg("clone")
-                .arg("--single-branch")
-                .arg("--recursive")
-                .arg(format!("https://git::@github.com/{plugin}"))
-                .arg(dir_name)
-                .current_dir(path)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .status()
-                .await?
-        }
-    };
+    let mut command = Command::new("git");
+    command.arg("clone");
+    if let Some(b) = branch {
+        command.arg("-b").arg(b);
+    }
+    let output = command
+        .arg("--single-branch")
+        .arg("--recursive")
+        .arg(&url)
+        .arg(dir_name)
+        .current_dir(path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
 
-    if !status.success() {
-        eprintln!("Git failed: {plugin}");
+    let status = output.status;
+    match status.success() {
+        true => log::info(&format!("clone {plugin}: ok")),
+        false => {
+            let error = git_error(&output.stderr);
+            log::report(&format!("Git failed: {plugin}: {error}"));
+            log::error(&format!(
+                "clone {plugin}: failed with status {status}: {error}"
+            ));
+        }
     }
 
     Ok(status)
@@ -91,32 +160,97 @@ pub async fn git_pull(plugin: &String) -> io::Result<ExitStatus> {
     let mut path = Path::Plugins.get();
     path.push(plugin);
 
-    let pull_status = Command::new("git")
+    let pull = Command::new("git")
         .arg("pull")
         .current_dir(&path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
         .stdout(Stdio::null())
-        .status()
+        .stderr(Stdio::piped())
+        .output()
         .await?;
-    let submodule_status = Command::new("git")
+    let submodule = Command::new("git")
         .arg("submodule")
         .arg("update")
         .arg("--init")
         .arg("--recursive")
         .current_dir(&path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
         .stdout(Stdio::null())
-        .status()
+        .stderr(Stdio::piped())
+        .output()
         .await?;
 
-    if !pull_status.success() {
-        eprintln!("Git failed: {plugin}");
+    let pull_status = pull.status;
+    match pull_status.success() {
+        true => log::info(&format!("pull {plugin}: ok")),
+        false => {
+            let error = git_error(&pull.stderr);
+            log::report(&format!("Git failed: {plugin}: {error}"));
+            log::error(&format!(
+                "pull {plugin}: failed with status {pull_status}: {error}"
+            ));
+        }
     }
-    if !submodule_status.success() {
-        eprintln!("Git failed: {plugin}");
+    if !submodule.status.success() {
+        let error = git_error(&submodule.stderr);
+        log::report(&format!("Git failed: {plugin}: {error}"));
+        log::error(&format!(
+            "pull {plugin}: submodule update failed with status {}: {error}",
+            submodule.status
+        ));
     }
 
     Ok(pull_status)
 }
 
+pub async fn git_head(plugin: &str) -> Option<String> {
+    let mut path = Path::Plugins.get();
+    path.push(plugin);
+
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(&path)
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    match output.status.success() {
+        true => Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => None,
+    }
+}
+
+// Moves the clone's branch back to `commit`, so a later pull still
+// fast-forwards instead of leaving a detached HEAD behind.
+pub async fn git_reset(plugin: &str, commit: &str) -> io::Result<ExitStatus> {
+    let mut path = Path::Plugins.get();
+    path.push(plugin);
+
+    let output = Command::new("git")
+        .args(["reset", "--hard", commit])
+        .current_dir(&path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+
+    let status = output.status;
+    match status.success() {
+        true => log::info(&format!("reset {plugin} to {commit}: ok")),
+        false => {
+            let error = git_error(&output.stderr);
+            log::report(&format!("Git failed: {plugin}: {error}"));
+            log::error(&format!(
+                "reset {plugin} to {commit}: failed with status {status}: {error}"
+            ));
+        }
+    }
+
+    Ok(status)
+}
+
 pub async fn check_for_update(plugin: &str) -> io::Result<(String, String)> {
     let mut path = Path::Plugins.get();
     path.push(format_plugin_dir_name(plugin));
@@ -146,15 +280,29 @@ pub fn remove_dir(path: String) -> io::Result<()> {
     Ok(())
 }
 
-pub async fn clone() -> io::Result<()> {
+pub struct ManifestEntry {
+    pub repo: String,
+    pub branch: Option<String>,
+    pub disabled: bool,
+}
+
+// Disabled plugins stay in plugins.conf, commented out, so they keep their
+// branch and clone and can be enabled again.
+pub const DISABLED_PREFIX: &str = "# disabled:";
+
+pub fn read_manifest() -> io::Result<Vec<ManifestEntry>> {
     let path = Path::PluginsConfig.get();
     let file = File::open(path)?;
     let reader = BufReader::new(file);
 
-    let mut handles = vec![];
-
+    let mut entries = vec![];
     for line_result in reader.lines() {
         let line = line_result?;
+        let (line, disabled) = match line.trim_start().strip_prefix(DISABLED_PREFIX) {
+            Some(rest) => (rest.to_string(), true),
+            None if line.trim_start().starts_with('#') => continue,
+            None => (line, false),
+        };
         let repo_and_branch: Vec<_> = line.split_whitespace().collect();
 
         let repo = if !repo_and_branch.is_empty() {
@@ -168,6 +316,20 @@ pub async fn clone() -> io::Result<()> {
             None
         };
 
+        entries.push(ManifestEntry {
+            repo,
+            branch,
+            disabled,
+        });
+    }
+
+    Ok(entries)
+}
+
+pub async fn clone() -> io::Result<()> {
+    let mut handles = vec![];
+
+    for ManifestEntry { repo, branch, .. } in read_manifest()? {
         if !check_if_plugin_already_cloned(&repo) {
             let handle = task::spawn(async move {
                 let _ = git_clone(&repo, branch).await;
@@ -179,7 +341,7 @@ pub async fn clone() -> io::Result<()> {
 
     for handle in handles {
         if let Err(e) = handle.await {
-            eprintln!("Task failed: {e:?}");
+            log::report(&format!("Task failed: {e:?}"));
         }
     }
 
@@ -210,29 +372,185 @@ pub async fn pull() -> io::Result<()> {
     }
     for handle in handles {
         if let Err(e) = handle.await {
-            eprintln!("Task failed: {e:?}");
+            log::report(&format!("Task failed: {e:?}"));
         }
     }
 
     Ok(())
 }
 
-pub fn run_plugins() {
+pub enum PluginOutcome {
+    Success,
+    Failed(ExitStatus),
+    TimedOut(Duration),
+    Error(io::Error),
+}
+
+pub struct PluginRun {
+    pub path: String,
+    pub outcome: PluginOutcome,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+impl PluginRun {
+    pub fn is_success(&self) -> bool {
+        matches!(self.outcome, PluginOutcome::Success)
+    }
+}
+
+impl Display for PluginRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            PluginOutcome::Success => write!(f, "Plugin succeeded: {}", self.path)?,
+            PluginOutcome::Failed(status) => write!(f, "Plugin failed ({status}): {}", self.path)?,
+            PluginOutcome::TimedOut(timeout) => write!(
+                f,
+                "Plugin timed out after {}s: {}",
+                timeout.as_secs(),
+                self.path
+            )?,
+            PluginOutcome::Error(e) => write!(f, "Failed to run plugin {}: {e}", self.path)?,
+        }
+        let stderr = self.stderr.trim();
+        if !stderr.is_empty() {
+            write!(f, "\n{stderr}")?;
+        }
+        Ok(())
+    }
+}
+
+async fn run_plugin(path: String, timeout: Duration) -> PluginRun {
+    let start = Instant::now();
+    let mut run = PluginRun {
+        path,
+        outcome: PluginOutcome::Success,
+        stderr: String::new(),
+        elapsed: Duration::ZERO,
+    };
+
+    // The plugin leads its own process group, so that whatever it started is
+    // killed with it when it times out.
+    let child = Command::new(&run.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            run.outcome = PluginOutcome::Error(e);
+            run.elapsed = start.elapsed();
+            return run;
+        }
+    };
+
+    let pid = child.id();
+    run.outcome = match time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if !stdout.trim().is_empty() {
+                log::info(&format!("Plugin output: {}\n{}", run.path, stdout.trim()));
+            }
+            run.stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            match output.status.success() {
+                true => PluginOutcome::Success,
+                false => PluginOutcome::Failed(output.status),
+            }
+        }
+        Ok(Err(e)) => PluginOutcome::Error(e),
+        Err(_) => {
+            if let Some(pid) = pid {
+                kill_process_group(pid);
+            }
+            PluginOutcome::TimedOut(timeout)
+        }
+    };
+    run.elapsed = start.elapsed();
+    run
+}
+
+fn kill_process_group(pgid: u32) {
+    let _ = std::process::Command::new("kill")
+        .args(["-KILL", "--", &format!("-{pgid}")])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+fn is_entry_point(plugins_dir: &std::path::Path, file: &std::path::Path) -> bool {
+    let Ok(relative) = file.strip_prefix(plugins_dir) else {
+        return false;
+    };
+    let Some(dir_name) = relative.iter().next() else {
+        return false;
+    };
+
+    let registry_entry = Registry::get().find_by_dir_name(&dir_name.to_string_lossy());
+    match registry_entry.and_then(|e| e.entry.as_ref()) {
+        Some(entry) => relative == std::path::Path::new(dir_name).join(entry),
+        None => file.display().to_string().ends_with(".tmux"),
+    }
+}
+
+// Runs the entry points of a single plugin cloned into `dir`, whose name
+// follows the plugins directory layout.
+pub async fn run_plugin_dir(dir: &std::path::Path) -> Vec<PluginRun> {
+    let Some(base) = dir.parent() else {
+        return vec![];
+    };
+    let timeout = Config::load().plugin_timeout();
+
+    let mut runs = vec![];
+    for entry in WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| is_entry_point(base, e.path()))
+    {
+        let run = run_plugin(entry.path().display().to_string(), timeout).await;
+        match run.is_success() {
+            true => log::info(&run.to_string()),
+            false => log::error(&run.to_string()),
+        }
+        runs.push(run);
+    }
+    runs
+}
+
+pub async fn run_plugins() -> Vec<PluginRun> {
     let path = Path::Plugins.get();
+    let timeout = Config::load().plugin_timeout();
+
+    let disabled: Vec<_> = read_manifest()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.disabled)
+        .map(|entry| path.join(format_plugin_dir_name(&entry.repo)))
+        .collect();
 
     let plugins: Vec<_> = WalkDir::new(&path)
         .into_iter()
         .filter_map(Result::ok)
         .filter(|e| e.file_type().is_file())
-        .filter(|e| e.path().display().to_string().ends_with(".tmux"))
+        .filter(|e| !disabled.iter().any(|dir| e.path().starts_with(dir)))
+        .filter(|e| is_entry_point(&path, e.path()))
         .collect();
 
-    if plugins.is_empty() {
-        return;
-    }
-
+    let mut runs = vec![];
     for entry in plugins {
-        let arguments = vec![entry.path().display().to_string()];
-        TmuxCommand::RunShell.run(arguments);
+        let run = run_plugin(entry.path().display().to_string(), timeout).await;
+        match run.is_success() {
+            true => log::info(&run.to_string()),
+            false => {
+                log::report(&run.to_string());
+                log::error(&run.to_string());
+            }
+        }
+        runs.push(run);
     }
+    runs
 }
@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    log,
+    plugins::{clone_url, git_error, run_plugin_dir},
+    register::Registry,
+    tmuxedo::Path,
+    utils::format_plugin_dir_name,
+};
+
+const THEME_CATEGORY: &str = "themes";
+const RESTORE_FILE: &str = "restore.conf";
+
+// Themes set session, window and server options, so each scope is listed
+// and restored with its own `set-option` flags.
+const SCOPES: [&str; 3] = ["-g", "-wg", "-s"];
+
+pub fn is_theme(plugin: &str) -> bool {
+    Registry::get()
+        .find(plugin)
+        .is_some_and(|e| e.category == THEME_CATEGORY)
+}
+
+// Maps (scope, option name) to the option's `show-options` line, which tmux
+// quotes so that it can be replayed after `set-option`.
+struct Snapshot(HashMap<(&'static str, String), String>);
+
+impl Snapshot {
+    fn take() -> Result<Self, String> {
+        let mut options = HashMap::new();
+        for scope in SCOPES {
+            let output = Command::new("tmux")
+                .args(["show-options", scope])
+                .stdin(Stdio::null())
+                .output()
+                .map_err(|e| format!("Failed to run tmux: {e}"))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "tmux show-options {scope} failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                if let Some(name) = line.split_whitespace().next() {
+                    options.insert((scope, name.to_string()), line.to_string());
+                }
+            }
+        }
+        Ok(Self(options))
+    }
+
+    // Commands that take the server from `current` back to this snapshot:
+    // options the preview added are unset and changed ones are set again.
+    fn restore_from(&self, current: &Self) -> Vec<String> {
+        let mut commands: Vec<String> = current
+            .0
+            .keys()
+            .filter(|key| !self.0.contains_key(*key))
+            .map(|(scope, name)| format!("set-option {scope}u {name}"))
+            .collect();
+        commands.extend(
+            self.0
+                .iter()
+                .filter(|(key, line)| current.0.get(*key) != Some(line))
+                .map(|((scope, _), line)| format!("set-option {scope} {line}")),
+        );
+        commands
+    }
+}
+
+pub struct ThemePreview {
+    pub plugin: String,
+    dir: PathBuf,
+    saved: Snapshot,
+}
+
+impl ThemePreview {
+    // Clones the theme into a scratch directory and runs it against the
+    // running server, remembering the options it had beforehand.
+    pub async fn start(plugin: &str) -> Result<Self, String> {
+        let saved = Snapshot::take()?;
+        let previews = Path::Previews.get();
+        let dir = previews.join(format_plugin_dir_name(plugin));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&previews).map_err(|e| format!("Failed to create {previews:?}: {e}"))?;
+
+        let mut command = tokio::process::Command::new("git");
+        command.args(["clone", "--depth", "1", "--recursive"]);
+        if let Some(branch) = Registry::get().find(plugin).and_then(|e| e.branch.clone()) {
+            command.arg("-b").arg(branch);
+        }
+        let output = command
+            .arg(clone_url(plugin))
+            .arg(&dir)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run git: {e}"))?;
+        if !output.status.success() {
+            let error = git_error(&output.stderr);
+            log::error(&format!("preview {plugin}: clone failed: {error}"));
+            return Err(format!("Could not clone {plugin}: {error}"));
+        }
+
+        let preview = Self {
+            plugin: plugin.to_string(),
+            dir,
+            saved,
+        };
+        let runs = run_plugin_dir(&preview.dir).await;
+        let failure = match runs.iter().find(|run| !run.is_success()) {
+            Some(run) => Some(run.to_string()),
+            None if runs.is_empty() => Some(format!("{plugin} has no entry point to run")),
+            None => None,
+        };
+        match failure {
+            Some(error) => {
+                let _ = preview.revert();
+                Err(error.lines().next().unwrap_or_default().to_string())
+            }
+            None => {
+                log::info(&format!("preview {plugin}: applied"));
+                Ok(preview)
+            }
+        }
+    }
+
+    pub fn revert(self) -> Result<(), String> {
+        let commands = self.saved.restore_from(&Snapshot::take()?);
+        let file = self.dir.with_file_name(RESTORE_FILE);
+        fs::write(&file, commands.join("\n"))
+            .map_err(|e| format!("Failed to write {}: {e}", file.display()))?;
+
+        let output = Command::new("tmux")
+            .arg("source-file")
+            .arg(&file)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run tmux: {e}"))?;
+        let _ = fs::remove_file(&file);
+        self.discard();
+
+        match output.status.success() {
+            true => {
+                log::info(&format!("preview {}: reverted", self.plugin));
+                Ok(())
+            }
+            false => {
+                let error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                log::error(&format!("preview {}: revert failed: {error}", self.plugin));
+                Err(error)
+            }
+        }
+    }
+
+    // Keeps the applied options, e.g. once the theme has been installed.
+    pub fn discard(&self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
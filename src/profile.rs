@@ -0,0 +1,99 @@
+use std::{cmp::Reverse, time::Duration};
+
+use crate::{
+    plugins::{PluginRun, run_plugins},
+    register::sync_registries,
+    tmuxedo::{ensure_structure, source_all_tmuxedo_files},
+};
+
+pub struct Timing {
+    pub command: String,
+    pub target: String,
+    pub elapsed: Duration,
+}
+
+impl Timing {
+    pub fn new(command: &str, target: String, elapsed: Duration) -> Self {
+        Self {
+            command: command.to_string(),
+            target,
+            elapsed,
+        }
+    }
+
+    fn millis(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0
+    }
+}
+
+impl From<&PluginRun> for Timing {
+    fn from(run: &PluginRun) -> Self {
+        Self::new("run-shell", run.path.clone(), run.elapsed)
+    }
+}
+
+pub async fn profile_startup(update: bool, json: bool) {
+    ensure_structure();
+    sync_registries(update).await;
+    let mut timings = source_all_tmuxedo_files(update).await;
+    timings.extend(run_plugins().await.iter().map(Timing::from));
+    timings.sort_by_key(|t| Reverse(t.elapsed));
+
+    match json {
+        true => print_json(&timings),
+        false => print_table(&timings),
+    }
+}
+
+fn print_table(timings: &[Timing]) {
+    let command_width = timings
+        .iter()
+        .map(|t| t.command.len())
+        .chain(["Command".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!("{:>10}  {:<command_width$}  Target", "Time (ms)", "Command");
+    for timing in timings {
+        println!(
+            "{:>10.1}  {:<command_width$}  {}",
+            timing.millis(),
+            timing.command,
+            timing.target
+        );
+    }
+
+    let total: Duration = timings.iter().map(|t| t.elapsed).sum();
+    println!("{:>10.1}  total", total.as_secs_f64() * 1000.0);
+}
+
+fn print_json(timings: &[Timing]) {
+    let entries: Vec<String> = timings
+        .iter()
+        .map(|t| {
+            format!(
+                "{{\"command\":\"{}\",\"target\":\"{}\",\"millis\":{:.3}}}",
+                escape_json(&t.command),
+                escape_json(&t.target),
+                t.millis()
+            )
+        })
+        .collect();
+    println!("[{}]", entries.join(","));
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
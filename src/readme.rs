@@ -0,0 +1,71 @@
+use std::{fs, path::PathBuf};
+
+use regex::Regex;
+
+use crate::{tmuxedo::Path, utils::format_plugin_dir_name};
+
+#[derive(Default)]
+pub struct Readme {
+    pub plugin: String,
+    pub installed: bool,
+    pub text: Option<String>,
+    pub options: Vec<String>,
+}
+
+impl Readme {
+    pub fn load(plugin: &str, installed: bool) -> Self {
+        let dir_name = format_plugin_dir_name(plugin);
+        let file = match installed {
+            true => find_readme(&Path::Plugins.get().join(&dir_name)),
+            false => Some(cached_readme(&dir_name)),
+        };
+        let text = file.and_then(|file| fs::read_to_string(file).ok());
+        let options = text.as_deref().map(extract_options).unwrap_or_default();
+
+        Self {
+            plugin: plugin.to_string(),
+            installed,
+            text,
+            options,
+        }
+    }
+}
+
+pub fn is_readme(name: &str) -> bool {
+    name.to_lowercase().starts_with("readme")
+}
+
+fn find_readme(dir: &std::path::Path) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| is_readme(&name.to_string_lossy()))
+        })
+        .collect();
+    files.sort();
+    files.into_iter().next()
+}
+
+pub fn cached_readme(dir_name: &str) -> PathBuf {
+    let mut path = Path::Readmes.get();
+    path.push(format!("{dir_name}.md"));
+    path
+}
+
+// Plugins document their options as `set -g @option value` lines, which
+// avoids picking up e-mail addresses and GitHub mentions.
+fn extract_options(text: &str) -> Vec<String> {
+    let re = Regex::new(r"set(?:-option)?\s+(?:-\w+\s+)*(@[\w-]+)").unwrap();
+    let mut options: Vec<String> = vec![];
+    for caps in re.captures_iter(text) {
+        let option = caps[1].to_string();
+        if !options.contains(&option) {
+            options.push(option);
+        }
+    }
+    options
+}
@@ -1,143 +1,347 @@
-pub enum TmuxPlugins {
-    Themes,
-    StatusBar,
-    Plugins,
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::PathBuf,
+    process::Stdio,
+    sync::OnceLock,
+};
+
+use dirs::home_dir;
+use ini::{Ini, Properties};
+use tokio::process::Command;
+
+use crate::{
+    config::Config,
+    log,
+    tmuxedo::Path,
+    utils::{format_plugin_dir_name, is_git_url},
+};
+
+const BUNDLED_REGISTRY: &str = include_str!("registry.ini");
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+// Where an entry comes from. Team registries are told apart by name, which
+// can be anything, so the built-in ones are separate variants.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub enum RegistrySource {
+    #[default]
+    Bundled,
+    User,
+    Team(String),
 }
 
-impl TmuxPlugins {
-    fn themes(&self) -> Vec<String> {
-        let known_plugins = vec![
-            "catppuccin/tmux",
-            "dracula/tmux",
-            "niksingh710/minimal-tmux-status",
-            "arcticicestudio/nord-tmux",
-            "rose-pine/tmux",
-            "jatap/tmux-base16-statusline",
-            "seebi/tmux-colors-solarized",
-            "erikw/tmux-dark-notify",
-            "egel/tmux-gruvbox",
-            "o0th/tmux-nova",
-            "wfxr/tmux-power",
-            "ivnvxd/tmux-snazzy",
-            "jimeh/tmux-themepack",
-            "fabioluciano/tmux-tokyo-night",
-            "janoamaral/tokyo-night-tmux",
-            "edouard-lopez/tmux-tomorrow",
-            "Nybkox/tmux-kanagawa",
-        ];
-        known_plugins.iter().map(|e| e.to_string()).collect()
-    }
-
-    fn status_bar(&self) -> Vec<String> {
-        let known_plugins = vec![
-            "darko-mesaros/aws-tmux",
-            "arl/gitmux",
-            "dlurak/muxbar",
-            "2KAbhishek/tmux2k",
-            "tmux-plugins/tmux-battery",
-            "vascomfnunes/tmux-clima",
-            "Determinant/tmux-colortag",
-            "jdxcode/tmux-cpu-info",
-            "tmux-plugins/tmux-cpu",
-            "tony-sol/tmux-current-pane-hostname",
-            "tassaron/tmux-df",
-            "vascomfnunes/tmux-kripto",
-            "tony-sol/tmux-kubectx",
-            "tmux-plugins/tmux-maildir-counter",
-            "thewtex/tmux-mem-cpu-load",
-            "MunifTanjim/tmux-mode-indicator",
-            "Feqzz/tmux-mpv-info",
-            "jaclu/tmux-mullvad",
-            "vascomfnunes/tmux-ludanta",
-            "joshmedeski/tmux-nerd-font-window-name",
-            "xamut/tmux-network-bandwidth",
-            "maxrodrigo/tmux-nordvpn",
-            "tmux-plugins/tmux-online-status",
-            "jaclu/tmux-packet-loss",
-            "Brutuski/tmux-piavpn",
-            "richin13/tmux-plugin-playerctl",
-            "olimorris/tmux-pomodoro-plus",
-            "tmux-plugins/tmux-prefix-highlight",
-            "charlietag/tmux-split-statusbar",
-            "jdxcode/tmux-spotify-info",
-            "Feqzz/tmux-spotify-info",
-            "TheSast/tmux-transient-status",
-            "jdxcode/tmux-weather",
-            "xamut/tmux-weather",
-            "ofirgall/tmux-window-name",
-            "Feqzz/tmux-weather-info-yr",
-            "alexanderjeurissen/tmux-world-clock",
-            "sjdonado/tmux-workspace-usage",
-        ];
-        known_plugins.iter().map(|e| e.to_string()).collect()
-    }
-
-    fn plugins(&self) -> Vec<String> {
-        let known_plugins = vec![
-            "AranBorkum/tmux-cookie-cutter",
-            "hamolicious/back-in-5",
-            "tweekmonster/tmux2html",
-            "NHDaly/tmux-better-mouse-mode",
-            "laktak/extrakto",
-            "tardunge/kmux-status",
-            "bjesus/muxile",
-            "b0o/tmux-autoreload",
-            "Alkindi42/tmux-bitwarden",
-            "ofirgall/tmux-browser",
-            "kenos1/tmux-cht-sh",
-            "aless3/tmux-click-copy",
-            "lost-melody/tmux-command-palette",
-            "CrispyConductor/tmux-copy-toolkit",
-            "IngoMeyer441/tmux-easy-motion",
-            "ddzero2c/tmux-easymotion",
-            "MaximilianGaedig/tmux-filter",
-            "Morantron/tmux-fingers",
-            "lloydbond/tmux-floating-terminal",
-            "roosta/tmux-fuzzback",
-            "wfxr/tmux-fzf-url",
-            "thuanOwa/tmux-fzf-session-switch",
-            "tardunge/tmux-gh",
-            "TheSast/tmux-keylocker",
-            "jaclu/tmux-menus",
-            "whame/tmux-modal",
-            "jaclu/tmux-mouse-swipe",
-            "rickstaa/tmux-notify",
-            "trevarj/tmux-open-nvim",
-            "Peter-McKinney/tmux-fzf-open-files-nvim",
-            "fcsonline/tmux-thumbs",
-            "yardnsm/tmux-1password",
-            "schasse/tmux-jump",
-            "jaclu/tmux-power-zoom",
-            "27medkamal/tmux-session-wizard",
-            "kristijanhusak/tmux-simple-git-status",
-            "xamut/tmux-spotify",
-            "danjeltahko/spotify-tmux",
-            "artemave/tmux_super_fingers",
-            "jabirali/tmux-tilish",
-            "2KAbhishek/tmux-tilit",
-            "croxarens/tmux-timetrap",
-            "gcla/tmux-wormhole",
-            "GoHarder/tmux-pianobar",
-            "tmux-plugins/tmux-continuum",
-            "tmux-plugins/tmux-copycat",
-            "tmux-plugins/tmux-fpp",
-            "tmux-plugins/tmux-logging",
-            "tmux-plugins/tmux-open",
-            "tmux-plugins/tmux-pain-control",
-            "tmux-plugins/tmux-resurrect",
-            "tmux-plugins/tmux-sessionist",
-            "tmux-plugins/tmux-sidebar",
-            "tmux-plugins/tmux-urlview",
-            "tmux-plugins/tmux-yank",
-        ];
-        known_plugins.iter().map(|e| e.to_string()).collect()
+impl Display for RegistrySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bundled => write!(f, "bundled"),
+            Self::User => write!(f, "user"),
+            Self::Team(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub category: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub homepage: String,
+    pub url: String,
+    pub branch: Option<String>,
+    pub entry: Option<String>,
+    pub source: RegistrySource,
+}
+
+impl RegistryEntry {
+    fn new(name: &str, source: &RegistrySource) -> Self {
+        Self {
+            name: name.to_string(),
+            homepage: format!("https://github.com/{name}"),
+            url: format!("https://git::@github.com/{name}"),
+            source: source.clone(),
+            ..Self::default()
+        }
     }
 
-    pub fn all(&self) -> Vec<String> {
+    fn apply(&mut self, properties: &Properties) {
+        for (key, value) in properties.iter() {
+            match key {
+                "category" => self.category = value.trim().to_lowercase().replace(' ', "_"),
+                "description" => self.description = value.to_string(),
+                "tags" => {
+                    self.tags = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(String::from)
+                        .collect()
+                }
+                "homepage" => self.homepage = value.to_string(),
+                "url" => self.url = value.to_string(),
+                "branch" => self.branch = Some(value.to_string()),
+                "entry" => self.entry = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+}
+
+pub enum RegistryLocation {
+    File(PathBuf),
+    Git(String),
+}
+
+impl RegistryLocation {
+    fn parse(value: &str) -> Self {
+        if is_git_url(value) {
+            return Self::Git(value.to_string());
+        }
+        match (value.strip_prefix("~/"), home_dir()) {
+            (Some(rest), Some(mut home)) => {
+                home.push(rest);
+                Self::File(home)
+            }
+            _ => Self::File(PathBuf::from(value)),
+        }
+    }
+
+    pub fn configured() -> Vec<Self> {
+        Config::load()
+            .get("registries")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(Self::parse)
+            .collect()
+    }
+
+    fn clone_dir(url: &str) -> PathBuf {
+        let mut dir = Path::Registries.get();
+        dir.push(format_plugin_dir_name(url));
+        dir
+    }
+
+    fn file(&self) -> PathBuf {
         match self {
-            Self::Themes => self.themes(),
-            Self::StatusBar => self.status_bar(),
-            Self::Plugins => self.plugins(),
+            Self::File(path) if path.is_dir() => path.join("registry.ini"),
+            Self::File(path) => path.clone(),
+            Self::Git(url) => Self::clone_dir(url).join("registry.ini"),
+        }
+    }
+
+    fn default_name(&self) -> String {
+        let path = match self {
+            Self::File(path) if path.is_dir() => path.clone(),
+            Self::File(path) => path.with_extension(""),
+            Self::Git(url) => PathBuf::from(url.trim_end_matches('/').trim_end_matches(".git")),
+        };
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("custom"))
+    }
+}
+
+pub async fn sync_registries(update: bool) {
+    for location in RegistryLocation::configured() {
+        let RegistryLocation::Git(url) = location else {
+            continue;
+        };
+        let dir = RegistryLocation::clone_dir(&url);
+
+        let status = match dir.exists() {
+            true if update => {
+                Command::new("git")
+                    .arg("pull")
+                    .current_dir(&dir)
+                    .env("GIT_TERMINAL_PROMPT", "0")
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .await
+            }
+            true => continue,
+            false => {
+                Command::new("git")
+                    .args(["clone", "--depth", "1", &url])
+                    .arg(&dir)
+                    .env("GIT_TERMINAL_PROMPT", "0")
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .await
+            }
+        };
+
+        match status {
+            Ok(status) if status.success() => log::info(&format!("registry {url}: ok")),
+            Ok(status) => {
+                log::report(&format!("Git failed: {url}"));
+                log::error(&format!("registry {url}: failed with status {status}"));
+            }
+            Err(e) => {
+                log::report(&format!("Git failed: {url}"));
+                log::error(&format!("registry {url}: failed to run git: {e}"));
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    entries: Vec<RegistryEntry>,
+    categories: Vec<String>,
+    sources: Vec<String>,
+}
+
+impl Registry {
+    pub fn get() -> &'static Self {
+        REGISTRY.get_or_init(Self::load)
+    }
+
+    fn load() -> Self {
+        let mut registry = Self::default();
+        if let Some(ini) = parse(BUNDLED_REGISTRY, "bundled registry") {
+            registry.merge(&ini, &RegistrySource::Bundled);
+        }
+
+        for location in RegistryLocation::configured() {
+            let file = location.file();
+            let content = match fs::read_to_string(&file) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::report(&format!("Failed to read registry {}: {e}", file.display()));
+                    continue;
+                }
+            };
+            if let Some(ini) = parse(&content, &file.display().to_string()) {
+                let source = ini
+                    .general_section()
+                    .get("name")
+                    .map(String::from)
+                    .unwrap_or_else(|| location.default_name());
+                registry.merge(&ini, &RegistrySource::Team(source.clone()));
+                if !registry.sources.contains(&source) {
+                    registry.sources.push(source);
+                }
+            }
+        }
+
+        let user_registry = Path::UserRegistry.get();
+        if let Ok(content) = fs::read_to_string(&user_registry)
+            && let Some(ini) = parse(&content, &user_registry.display().to_string())
+        {
+            registry.merge(&ini, &RegistrySource::User);
+        }
+
+        registry
+    }
+
+    fn merge(&mut self, ini: &Ini, source: &RegistrySource) {
+        for (section, properties) in ini.iter() {
+            let Some(name) = section else {
+                continue;
+            };
+
+            match self.entries.iter_mut().find(|e| e.name == name) {
+                Some(entry) => {
+                    entry.apply(properties);
+                    if *source != RegistrySource::User {
+                        entry.source = source.clone();
+                    }
+                }
+                None => {
+                    let mut entry = RegistryEntry::new(name, source);
+                    entry.apply(properties);
+                    // Team registries have a tab of their own, so their
+                    // entries are listed even without a category.
+                    match entry.category.is_empty() && !matches!(source, RegistrySource::Team(_)) {
+                        true => log::report(&format!(
+                            "Skipped {name} in the {source} registry: it has no category"
+                        )),
+                        false => self.entries.push(entry),
+                    }
+                }
+            }
+
+            if let Some(entry) = self.entries.iter().find(|e| e.name == name)
+                && !entry.category.is_empty()
+                && !self.categories.contains(&entry.category)
+            {
+                self.categories.push(entry.category.clone());
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries
+    }
+
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    pub fn find(&self, name: &str) -> Option<&RegistryEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn find_by_dir_name(&self, dir_name: &str) -> Option<&RegistryEntry> {
+        self.entries
+            .iter()
+            .find(|e| format_plugin_dir_name(&e.name) == dir_name)
+    }
+}
+
+fn parse(content: &str, origin: &str) -> Option<Ini> {
+    match Ini::load_from_str(content) {
+        Ok(ini) => Some(ini),
+        Err(e) => {
+            log::report(&format!("Failed to parse {origin}: {e}"));
+            None
         }
     }
 }
+
+pub fn category_title(category: &str) -> String {
+    category
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub enum TmuxPlugins {
+    All,
+    Category(String),
+    Source(String),
+}
+
+impl TmuxPlugins {
+    pub fn all(&self) -> Vec<String> {
+        Registry::get()
+            .entries()
+            .iter()
+            .filter(|e| match self {
+                Self::All => true,
+                Self::Category(category) => e.category == *category,
+                Self::Source(source) => {
+                    matches!(&e.source, RegistrySource::Team(name) if name == source)
+                }
+            })
+            .map(|e| e.name.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,540 @@
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ini::Ini;
+use regex::Regex;
+use tokio::{io::AsyncWriteExt, process::Command, sync::Semaphore, task};
+
+use crate::{
+    log,
+    readme::{cached_readme, is_readme},
+    register::{Registry, RegistryEntry},
+    tmuxedo::Path,
+    utils::format_plugin_dir_name,
+};
+
+const MAX_CONCURRENT_FETCHES: usize = 8;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Default)]
+pub struct CachedMetadata {
+    pub reachable: bool,
+    pub last_commit: Option<u64>,
+    pub default_branch: Option<String>,
+    pub archived: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Staleness {
+    Archived,
+    Gone,
+    Inactive(u64),
+}
+
+impl Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Archived => write!(f, "archived upstream"),
+            Self::Gone => write!(f, "remote is gone"),
+            Self::Inactive(days) => write!(f, "no commits for {days} days"),
+        }
+    }
+}
+
+fn now() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+pub fn local_last_commit(repo: &str) -> Option<u64> {
+    let mut dir = Path::Plugins.get();
+    dir.push(format_plugin_dir_name(repo));
+    if !dir.exists() {
+        return None;
+    }
+
+    let output = std::process::Command::new("git")
+        .args(["log", "-1", "--format=%ct"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[derive(Default)]
+pub struct RegistryCache {
+    pub refreshed: Option<u64>,
+    entries: HashMap<String, CachedMetadata>,
+}
+
+impl RegistryCache {
+    pub fn load() -> Self {
+        let Ok(ini) = Ini::load_from_file(Path::RegistryCache.get()) else {
+            return Self::default();
+        };
+
+        let mut cache = Self {
+            refreshed: ini
+                .general_section()
+                .get("refreshed")
+                .and_then(|v| v.parse().ok()),
+            entries: HashMap::new(),
+        };
+        for (section, properties) in ini.iter() {
+            let Some(name) = section else {
+                continue;
+            };
+            let metadata = CachedMetadata {
+                reachable: properties.get("reachable") == Some("true"),
+                last_commit: properties.get("last_commit").and_then(|v| v.parse().ok()),
+                default_branch: properties.get("default_branch").map(String::from),
+                archived: properties.get("archived").and_then(|v| v.parse().ok()),
+            };
+            cache.entries.insert(name.to_string(), metadata);
+        }
+        cache
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let mut ini = Ini::new();
+        if let Some(refreshed) = self.refreshed {
+            ini.with_general_section()
+                .set("refreshed", refreshed.to_string());
+        }
+
+        let mut names: Vec<&String> = self.entries.keys().collect();
+        names.sort();
+        for name in names {
+            let metadata = &self.entries[name];
+            let mut section = ini.with_section(Some(name.as_str()));
+            section.set("reachable", metadata.reachable.to_string());
+            if let Some(last_commit) = metadata.last_commit {
+                section.set("last_commit", last_commit.to_string());
+            }
+            if let Some(default_branch) = &metadata.default_branch {
+                section.set("default_branch", default_branch.as_str());
+            }
+            if let Some(archived) = metadata.archived {
+                section.set("archived", archived.to_string());
+            }
+        }
+
+        let path = Path::RegistryCache.get();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        ini.write_to_file(path)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CachedMetadata> {
+        self.entries.get(name)
+    }
+
+    // The cache only knows about registry entries, so the local clone's history
+    // covers plugins added by hand, and whichever commit is newer wins.
+    pub fn staleness(&self, repo: &str, stale_after: Duration) -> Option<Staleness> {
+        self.staleness_since(repo, stale_after, local_last_commit(repo))
+    }
+
+    fn staleness_since(
+        &self,
+        repo: &str,
+        stale_after: Duration,
+        local_last_commit: Option<u64>,
+    ) -> Option<Staleness> {
+        let metadata = self.get(repo);
+        if metadata.is_some_and(|m| m.archived == Some(true)) {
+            return Some(Staleness::Archived);
+        }
+        if metadata.is_some_and(|m| !m.reachable) {
+            return Some(Staleness::Gone);
+        }
+
+        let last_commit = metadata
+            .and_then(|m| m.last_commit)
+            .max(local_last_commit)?;
+        let idle = now()?.saturating_sub(last_commit);
+        match idle > stale_after.as_secs() {
+            true => Some(Staleness::Inactive(idle / SECS_PER_DAY)),
+            false => None,
+        }
+    }
+}
+
+async fn git_output(args: &[&str], dir: Option<&std::path::Path>) -> Option<String> {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+
+    let output = command.output().await.ok()?;
+    match output.status.success() {
+        true => Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => None,
+    }
+}
+
+// Best effort: GitHub allows 60 unauthenticated API requests an hour, so a
+// refresh of a large registry only learns the archived flag for some entries
+// unless `GITHUB_TOKEN` is set. The token goes to curl on stdin so it never
+// shows up in the process list.
+async fn fetch_archived(url: &str) -> Option<bool> {
+    let re = Regex::new(r"github\.com[/:]([^/]+)/([^/]+?)(\.git)?/?$").unwrap();
+    let caps = re.captures(url)?;
+    let api_url = format!("https://api.github.com/repos/{}/{}", &caps[1], &caps[2]);
+
+    let mut child = Command::new("curl")
+        .args([
+            "-sfL",
+            "-H",
+            "Accept: application/vnd.github+json",
+            "-H",
+            "@-",
+            &api_url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let header = match env::var("GITHUB_TOKEN") {
+            Ok(token) if !token.is_empty() => format!("Authorization: Bearer {token}\n"),
+            _ => String::new(),
+        };
+        let _ = stdin.write_all(header.as_bytes()).await;
+    }
+    let output = child.wait_with_output().await.ok()?;
+    let body = String::from_utf8_lossy(&output.stdout);
+    let re = Regex::new(r#""archived"\s*:\s*(true|false)"#).unwrap();
+    re.captures(&body).map(|caps| &caps[1] == "true")
+}
+
+async fn save_readme(scratch: &std::path::Path, readme: &std::path::Path) {
+    let Some(files) = git_output(&["ls-tree", "--name-only", "FETCH_HEAD"], Some(scratch)).await
+    else {
+        return;
+    };
+    let mut readmes: Vec<&str> = files.lines().filter(|name| is_readme(name)).collect();
+    readmes.sort();
+    let Some(name) = readmes.first() else {
+        return;
+    };
+
+    let object = format!("FETCH_HEAD:{name}");
+    if let Some(text) = git_output(&["show", &object], Some(scratch)).await {
+        if let Some(parent) = readme.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(readme, text);
+    }
+}
+
+// The remote's HEAD is fetched into a throwaway bare repository at `scratch`,
+// and its README is kept at `readme`.
+async fn fetch_metadata(
+    entry: &RegistryEntry,
+    scratch: &std::path::Path,
+    readme: &std::path::Path,
+) -> CachedMetadata {
+    let mut metadata = CachedMetadata::default();
+
+    let Some(heads) = git_output(&["ls-remote", "--symref", &entry.url, "HEAD"], None).await else {
+        return metadata;
+    };
+    metadata.reachable = true;
+    let re = Regex::new(r"ref: refs/heads/(\S+)\s+HEAD").unwrap();
+    metadata.default_branch = re.captures(&heads).map(|caps| caps[1].to_string());
+
+    let _ = fs::remove_dir_all(scratch);
+    if fs::create_dir_all(scratch).is_ok()
+        && git_output(&["init", "--bare", "--quiet"], Some(scratch))
+            .await
+            .is_some()
+        && git_output(
+            &["fetch", "--depth", "1", &entry.url, "HEAD"],
+            Some(scratch),
+        )
+        .await
+        .is_some()
+    {
+        metadata.last_commit =
+            git_output(&["log", "-1", "--format=%ct", "FETCH_HEAD"], Some(scratch))
+                .await
+                .and_then(|t| t.parse().ok());
+        save_readme(scratch, readme).await;
+    }
+    let _ = fs::remove_dir_all(scratch);
+
+    metadata.archived = fetch_archived(&entry.url).await;
+    metadata
+}
+
+// An unreachable remote keeps everything that was known about it, and a
+// missing archived flag (rate limit, no curl) keeps the previous one.
+fn merge_previous(metadata: CachedMetadata, previous: Option<&CachedMetadata>) -> CachedMetadata {
+    let Some(previous) = previous else {
+        return metadata;
+    };
+    match metadata.reachable {
+        true => CachedMetadata {
+            archived: metadata.archived.or(previous.archived),
+            ..metadata
+        },
+        false => CachedMetadata {
+            reachable: false,
+            ..previous.clone()
+        },
+    }
+}
+
+pub async fn refresh() {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut handles = vec![];
+
+    for entry in Registry::get().entries().iter().cloned() {
+        let semaphore = semaphore.clone();
+        handles.push(task::spawn(async move {
+            let _permit = semaphore.acquire().await;
+            let dir_name = format_plugin_dir_name(&entry.name);
+            let scratch = Path::Cache.get().join("scratch").join(&dir_name);
+            let metadata = fetch_metadata(&entry, &scratch, &cached_readme(&dir_name)).await;
+            (entry.name, metadata)
+        }));
+    }
+
+    let previous = RegistryCache::load();
+    let mut cache = RegistryCache::default();
+    for handle in handles {
+        match handle.await {
+            Ok((name, metadata)) => {
+                match metadata.reachable {
+                    true => log::info(&format!("registry refresh {name}: ok")),
+                    false => {
+                        eprintln!("Could not reach {name}");
+                        log::error(&format!("registry refresh {name}: remote unreachable"));
+                    }
+                }
+                let metadata = merge_previous(metadata, previous.get(&name));
+                cache.entries.insert(name, metadata);
+            }
+            Err(e) => eprintln!("Task failed: {e:?}"),
+        }
+    }
+
+    if cache.entries.values().all(|metadata| !metadata.reachable) {
+        eprintln!("Could not reach any remote, keeping the existing cache");
+        return;
+    }
+
+    cache.refreshed = now();
+    match cache.save() {
+        Ok(()) => println!(
+            "Refreshed metadata for {} plugin(s) into {}",
+            cache.entries.len(),
+            Path::RegistryCache.get().display()
+        ),
+        Err(e) => eprintln!("Failed to write registry cache: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, process};
+
+    use super::*;
+
+    const COMMIT_TIME: u64 = 1_700_000_000;
+
+    // A directory of its own for each test, removed when the test ends.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("tmuxedo-test-{}-{name}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_COMMITTER_DATE", format!("@{COMMIT_TIME} +0000"))
+            .env("GIT_AUTHOR_DATE", format!("@{COMMIT_TIME} +0000"))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    // A bare repository on `main` with a README, standing in for a remote.
+    fn bare_remote(root: &std::path::Path) -> PathBuf {
+        let work = root.join("work");
+        fs::create_dir_all(&work).unwrap();
+        git(&work, &["init", "--quiet", "--initial-branch", "main"]);
+        fs::write(work.join("README.md"), "# Test plugin\n").unwrap();
+        git(&work, &["add", "README.md"]);
+        git(&work, &["commit", "--quiet", "-m", "init"]);
+        git(root, &["clone", "--quiet", "--bare", "work", "bare.git"]);
+        root.join("bare.git")
+    }
+
+    fn entry(name: &str, url: String) -> RegistryEntry {
+        RegistryEntry {
+            name: name.to_string(),
+            url,
+            ..RegistryEntry::default()
+        }
+    }
+
+    fn cache_with(name: &str, metadata: CachedMetadata) -> RegistryCache {
+        RegistryCache {
+            refreshed: None,
+            entries: HashMap::from([(name.to_string(), metadata)]),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_metadata_reads_a_file_remote() {
+        let dir = TempDir::new("reachable");
+        let bare = bare_remote(&dir.0);
+        let url = format!("file://{}", bare.display());
+        let (scratch, readme) = (dir.0.join("scratch"), dir.0.join("readme.md"));
+
+        let metadata = fetch_metadata(&entry("test/reachable", url), &scratch, &readme).await;
+
+        assert!(metadata.reachable);
+        assert_eq!(metadata.default_branch.as_deref(), Some("main"));
+        assert_eq!(metadata.last_commit, Some(COMMIT_TIME));
+        assert_eq!(metadata.archived, None);
+        assert_eq!(fs::read_to_string(readme).unwrap().trim(), "# Test plugin");
+        assert!(!scratch.exists());
+    }
+
+    #[tokio::test]
+    async fn fetch_metadata_marks_a_missing_remote_unreachable() {
+        let dir = TempDir::new("missing");
+        let url = format!("file://{}", dir.0.join("missing.git").display());
+        let (scratch, readme) = (dir.0.join("scratch"), dir.0.join("readme.md"));
+
+        let metadata = fetch_metadata(&entry("test/missing", url), &scratch, &readme).await;
+
+        assert!(!metadata.reachable);
+        assert_eq!(metadata.last_commit, None);
+        assert!(!readme.exists());
+    }
+
+    #[test]
+    fn merge_previous_keeps_the_archived_flag() {
+        let previous = CachedMetadata {
+            reachable: true,
+            archived: Some(true),
+            ..CachedMetadata::default()
+        };
+        let fetched = CachedMetadata {
+            reachable: true,
+            last_commit: Some(COMMIT_TIME),
+            ..CachedMetadata::default()
+        };
+
+        let merged = merge_previous(fetched, Some(&previous));
+
+        assert_eq!(merged.archived, Some(true));
+        assert_eq!(merged.last_commit, Some(COMMIT_TIME));
+    }
+
+    #[test]
+    fn merge_previous_keeps_everything_for_an_unreachable_remote() {
+        let previous = CachedMetadata {
+            reachable: true,
+            last_commit: Some(COMMIT_TIME),
+            default_branch: Some(String::from("main")),
+            archived: Some(false),
+        };
+
+        let merged = merge_previous(CachedMetadata::default(), Some(&previous));
+
+        assert!(!merged.reachable);
+        assert_eq!(merged.last_commit, Some(COMMIT_TIME));
+        assert_eq!(merged.default_branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn staleness_reports_archived_plugins() {
+        let cache = cache_with(
+            "test/archived",
+            CachedMetadata {
+                reachable: true,
+                last_commit: now(),
+                archived: Some(true),
+                ..CachedMetadata::default()
+            },
+        );
+        let stale_after = Duration::from_secs(SECS_PER_DAY);
+
+        assert_eq!(
+            cache.staleness_since("test/archived", stale_after, None),
+            Some(Staleness::Archived)
+        );
+    }
+
+    #[test]
+    fn staleness_reports_gone_remotes() {
+        let cache = cache_with("test/gone", CachedMetadata::default());
+        let stale_after = Duration::from_secs(SECS_PER_DAY);
+
+        assert_eq!(
+            cache.staleness_since("test/gone", stale_after, None),
+            Some(Staleness::Gone)
+        );
+    }
+
+    #[test]
+    fn staleness_reports_inactive_plugins() {
+        let last_commit = now().unwrap() - 400 * SECS_PER_DAY;
+        let cache = cache_with(
+            "test/inactive",
+            CachedMetadata {
+                reachable: true,
+                last_commit: Some(last_commit),
+                ..CachedMetadata::default()
+            },
+        );
+        let year = Duration::from_secs(365 * SECS_PER_DAY);
+
+        assert_eq!(
+            cache.staleness_since("test/inactive", year, None),
+            Some(Staleness::Inactive(400))
+        );
+        assert_eq!(cache.staleness_since("test/inactive", year * 2, None), None);
+        assert_eq!(
+            cache.staleness_since("test/inactive", year, now()),
+            None,
+            "a newer local commit wins"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt::{self, Display};
+
+// Plugin lists are ordered by `SortMode` unless a search is active, in which
+// case the best matches come first.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    Name,
+    #[default]
+    Owner,
+    LastUpdated,
+    HasUpdate,
+    RecentlyInstalled,
+}
+
+impl SortMode {
+    const ALL: [Self; 5] = [
+        Self::Name,
+        Self::Owner,
+        Self::LastUpdated,
+        Self::HasUpdate,
+        Self::RecentlyInstalled,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Owner => write!(f, "owner"),
+            Self::LastUpdated => write!(f, "last updated"),
+            Self::HasUpdate => write!(f, "has update"),
+            Self::RecentlyInstalled => write!(f, "recently installed"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    #[default]
+    All,
+    Updates,
+    Disabled,
+    Pinned,
+}
+
+impl Filter {
+    const ALL: [Self; 4] = [Self::All, Self::Updates, Self::Disabled, Self::Pinned];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|filter| *filter == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Updates => write!(f, "with updates"),
+            Self::Disabled => write!(f, "disabled"),
+            Self::Pinned => write!(f, "pinned"),
+        }
+    }
+}
+
+// The repository part of `owner/repo` or of a git URL.
+pub fn repo_name(plugin: &str) -> &str {
+    plugin
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or(plugin)
+}
@@ -1,25 +1,50 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::fs::{self, OpenOptions};
 use std::io::{self, Write};
-use std::{
-    error::Error,
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader},
-};
+use std::path::{Component, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Duration, Instant, SystemTime};
 
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use tokio::task;
 
-use crate::plugins::{Plugin, check_for_update, remove_dir, run_plugins};
-use crate::utils::format_plugin_dir_name;
+use crate::TmuxCommand;
+use crate::bindings::Keymap;
+use crate::config::Config;
+use crate::log;
+use crate::plugins::{
+    DISABLED_PREFIX, ManifestEntry, Plugin, check_for_update, git_head, git_reset, read_manifest,
+    remove_dir, run_plugins, validate_plugin,
+};
+use crate::preview::{ThemePreview, is_theme};
+use crate::readme::Readme;
+use crate::utils::{format_plugin_dir_name, normalize_plugin};
 use crate::{
     plugins::{git_clone, git_pull},
-    register::TmuxPlugins,
-    tmuxedo::Path,
+    register::{Registry, TmuxPlugins},
+    registry_cache::{RegistryCache, Staleness, local_last_commit},
+    sort::{Filter, SortMode, repo_name},
+    tmuxedo::{Path, check_config_file, config_file_name, config_files, is_data_file},
     tui::WindowTab,
 };
 
+const LOG_ENTRIES: usize = 200;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+const MAX_NOTIFICATIONS: usize = 20;
+const NOTIFICATION_TTL: Duration = Duration::from_secs(10);
+const UNDO_LIMIT: usize = 20;
+
+pub struct Notification {
+    pub success: bool,
+    pub message: String,
+    pub at: Instant,
+}
+
 pub struct State {
+    pub keymap: Keymap,
     pub tab: WindowTab,
     pub selected_available_plugin_index: usize,
     pub selected_available_plugin_value: String,
@@ -29,52 +54,61 @@ pub struct State {
     pub all_installed_plugins: HashMap<String, Plugin>,
     pub search_mode: bool,
     pub search_string: String,
-    installed_themes: HashMap<String, Plugin>,
-    installed_status_bars: HashMap<String, Plugin>,
-    installed_plugins: HashMap<String, Plugin>,
-    available_themes: HashMap<String, Plugin>,
-    available_status_bars: HashMap<String, Plugin>,
-    available_plugins: HashMap<String, Plugin>,
+    pub add_mode: bool,
+    pub add_string: String,
+    pub add_error: Option<String>,
+    pub show_help: bool,
+    pub help_scroll: u16,
+    pub help_max_scroll: u16,
+    pub show_log: bool,
+    pub log_entries: Vec<String>,
+    pub registry_cache: RegistryCache,
+    pub stale_plugins: HashMap<String, Staleness>,
+    pub sort_mode: SortMode,
+    pub filter: Filter,
+    last_commits: HashMap<String, u64>,
+    installed_at: HashMap<String, SystemTime>,
+    pub readme: Readme,
+    pub readme_scroll: u16,
+    pub last_click: Option<(Instant, u16, u16)>,
+    pub pending_action: Option<PendingAction>,
+    pub marked: HashSet<String>,
+    pub notifications: Vec<Notification>,
+    pub preview: Option<ThemePreview>,
+    pub config_files: Vec<PathBuf>,
+    pub selected_config_index: usize,
+    pub config_text: Option<String>,
+    pub config_scroll: u16,
+    pub name_input: Option<NameInput>,
+    pub name_string: String,
+    pub name_error: Option<String>,
+    pub pending_edit: Option<PathBuf>,
+    pub undo_stack: Vec<UndoAction>,
+    undoing: bool,
 }
 
 impl State {
     async fn get_all_installed_plugins() -> HashMap<String, Plugin> {
-        let path = Path::PluginsConfig.get();
-        let file = match File::open(path) {
-            Ok(file) => file,
-            _ => todo!(),
-        };
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
-
         let mut plugins = HashMap::<String, Plugin>::new();
-        for line in lines {
-            let plugin = line;
-            plugins.insert(
-                plugin.clone(),
-                Plugin {
-                    path: plugin,
-                    commit_hash: "".to_string(),
-                    is_up_to_date: true,
-                },
-            );
+        for ManifestEntry {
+            repo,
+            branch,
+            disabled,
+        } in read_manifest().unwrap_or_default()
+        {
+            let mut plugin = Plugin::new(repo.clone(), branch);
+            plugin.disabled = disabled;
+            plugins.insert(repo, plugin);
         }
 
         plugins
     }
 
     pub async fn check_for_plugin_updated(&mut self) {
-        let path = Path::PluginsConfig.get();
-        let file = match File::open(path) {
-            Ok(file) => file,
-            _ => todo!(),
-        };
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
-
         let mut handles = vec![];
-        for line in lines {
-            handles.push(task::spawn(async move { check_for_update(&line).await }));
+        let plugins: Vec<String> = self.all_installed_plugins.keys().cloned().collect();
+        for plugin in plugins {
+            handles.push(task::spawn(async move { check_for_update(&plugin).await }));
         }
         for handle in handles {
             match handle.await {
@@ -84,54 +118,17 @@ impl State {
                     };
                 }
                 Ok(Err(err)) => {
-                    eprintln!("Error checking update: {err}");
+                    log::report(&format!("Error checking update: {err}"));
                 }
                 Err(join_err) => {
-                    eprintln!("Task join error: {join_err}");
+                    log::report(&format!("Task join error: {join_err}"));
                 }
             }
         }
     }
 
-    fn get_installed_and_available(
-        lines: &[String],
-        tmux_plugin: TmuxPlugins,
-    ) -> (HashMap<String, Plugin>, HashMap<String, Plugin>) {
-        let mut installed = HashMap::<String, Plugin>::new();
-        let mut available = HashMap::<String, Plugin>::new();
-
-        for item in tmux_plugin.all() {
-            let p = Plugin {
-                path: item.clone(),
-                commit_hash: "".to_string(),
-                is_up_to_date: true,
-            };
-            if lines.contains(&item) {
-                installed.insert(item.clone(), p);
-            } else {
-                available.insert(item.clone(), p);
-            }
-        }
-
-        (installed, available)
-    }
-
     pub async fn default() -> Self {
-        let path = Path::PluginsConfig.get();
-        let file = match File::open(path) {
-            Ok(file) => file,
-            _ => todo!(),
-        };
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
-
         let all_installed_plugins = Self::get_all_installed_plugins().await;
-        let installed_and_available_themes =
-            Self::get_installed_and_available(&lines, TmuxPlugins::Themes);
-        let installed_and_available_status_bars =
-            Self::get_installed_and_available(&lines, TmuxPlugins::StatusBar);
-        let installed_and_available_plugins =
-            Self::get_installed_and_available(&lines, TmuxPlugins::Plugins);
 
         let selected_available_plugin_value = String::new();
         let mut selected_installed_plugin_value = String::new();
@@ -142,7 +139,8 @@ impl State {
             selected_installed_plugin_value = all_installed_plugins_as_vec[0].clone();
         }
 
-        Self {
+        let mut state = Self {
+            keymap: Keymap::load(),
             tab: WindowTab::All,
             selected_available_plugin_index: 0,
             selected_available_plugin_value,
@@ -152,86 +150,422 @@ impl State {
             all_installed_plugins,
             search_mode: false,
             search_string: String::new(),
-            installed_themes: installed_and_available_themes.0,
-            installed_status_bars: installed_and_available_status_bars.0,
-            installed_plugins: installed_and_available_plugins.0,
-            available_themes: installed_and_available_themes.1,
-            available_status_bars: installed_and_available_status_bars.1,
-            available_plugins: installed_and_available_plugins.1,
+            add_mode: false,
+            add_string: String::new(),
+            add_error: None,
+            show_help: false,
+            help_scroll: 0,
+            help_max_scroll: 0,
+            show_log: false,
+            log_entries: Vec::new(),
+            registry_cache: RegistryCache::load(),
+            stale_plugins: HashMap::new(),
+            sort_mode: SortMode::default(),
+            filter: Filter::default(),
+            last_commits: HashMap::new(),
+            installed_at: HashMap::new(),
+            readme: Readme::default(),
+            readme_scroll: 0,
+            last_click: None,
+            pending_action: None,
+            marked: HashSet::new(),
+            notifications: Vec::new(),
+            preview: None,
+            config_files: Vec::new(),
+            selected_config_index: 0,
+            config_text: None,
+            config_scroll: 0,
+            name_input: None,
+            name_string: String::new(),
+            name_error: None,
+            pending_edit: None,
+            undo_stack: Vec::new(),
+            undoing: false,
+        };
+        state.find_stale_plugins();
+        state.load_plugin_dates();
+        state
+    }
+
+    pub fn find_stale_plugins(&mut self) {
+        let stale_after = Config::load().stale_after();
+        self.stale_plugins = self
+            .all_installed_plugins
+            .keys()
+            .filter_map(|plugin| {
+                self.registry_cache
+                    .staleness(plugin, stale_after)
+                    .map(|staleness| (plugin.clone(), staleness))
+            })
+            .collect();
+    }
+
+    // Sorting runs on every redraw, so the dates it needs are read once and
+    // refreshed after plugins are installed or updated.
+    pub fn load_plugin_dates(&mut self) {
+        let plugins_dir = Path::Plugins.get();
+        self.last_commits = self
+            .all_installed_plugins
+            .keys()
+            .filter_map(|plugin| local_last_commit(plugin).map(|time| (plugin.clone(), time)))
+            .collect();
+        self.installed_at = self
+            .all_installed_plugins
+            .keys()
+            .filter_map(|plugin| {
+                let metadata = fs::metadata(plugins_dir.join(format_plugin_dir_name(plugin)));
+                metadata
+                    .and_then(|m| m.created().or_else(|_| m.modified()))
+                    .ok()
+                    .map(|time| (plugin.clone(), time))
+            })
+            .collect();
+    }
+
+    fn has_update(&self, plugin: &str) -> bool {
+        self.all_installed_plugins
+            .get(plugin)
+            .is_some_and(|p| !p.commit_hash.is_empty())
+    }
+
+    fn last_updated(&self, plugin: &str) -> Option<u64> {
+        let cached = self
+            .registry_cache
+            .get(plugin)
+            .and_then(|metadata| metadata.last_commit);
+        self.last_commits.get(plugin).copied().max(cached)
+    }
+
+    fn passes_filter(&self, plugin: &str) -> bool {
+        let installed = self.all_installed_plugins.get(plugin);
+        match self.filter {
+            Filter::All => true,
+            Filter::Updates => self.has_update(plugin),
+            Filter::Disabled => installed.is_some_and(|p| p.disabled),
+            Filter::Pinned => installed.is_some_and(|p| p.branch.is_some()),
         }
     }
 
-    pub fn get_installed_plugins(&self) -> Vec<String> {
-        let mut plugins: Vec<_> = match self.tab {
-            WindowTab::All => self.all_installed_plugins.keys().cloned().collect(),
-            WindowTab::Themes => self.installed_themes.keys().cloned().collect(),
-            WindowTab::StatusBar => self.installed_status_bars.keys().cloned().collect(),
-            WindowTab::Plugins => self.installed_plugins.keys().cloned().collect(),
-        };
+    fn sort_plugins(&self, plugins: &mut [String]) {
+        plugins.sort();
+        match self.sort_mode {
+            SortMode::Owner => {}
+            SortMode::Name => plugins.sort_by_key(|p| repo_name(p).to_lowercase()),
+            SortMode::LastUpdated => plugins.sort_by_key(|p| Reverse(self.last_updated(p))),
+            SortMode::HasUpdate => plugins.sort_by_key(|p| !self.has_update(p)),
+            SortMode::RecentlyInstalled => {
+                plugins.sort_by_key(|p| Reverse(self.installed_at.get(p).copied()))
+            }
+        }
+    }
+
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.reset_selected_installed_plugin();
+        self.reset_selected_available_plugin();
+    }
+
+    pub fn cycle_filter(&mut self) {
+        self.filter = self.filter.next();
+        self.reset_selected_installed_plugin();
+        self.reset_selected_available_plugin();
+    }
+
+    // Shown next to the list titles, e.g. "sort: owner, filter: pinned".
+    pub fn list_view(&self) -> String {
+        match self.filter {
+            Filter::All => format!("sort: {}", self.sort_mode),
+            filter => format!("sort: {}, filter: {filter}", self.sort_mode),
+        }
+    }
+
+    fn search_score(&self, matcher: &SkimMatcherV2, plugin: &str) -> Option<i64> {
+        let tags = Registry::get()
+            .find(plugin)
+            .map(|e| e.tags.clone())
+            .unwrap_or_default();
+
+        std::iter::once(plugin)
+            .chain(tags.iter().map(String::as_str))
+            .filter_map(|text| matcher.fuzzy_match(text, &self.search_string))
+            .max()
+    }
 
+    fn filter_by_search(&self, mut plugins: Vec<String>) -> Vec<String> {
+        plugins.retain(|p| self.passes_filter(p));
         match self.search_string.is_empty() {
             true => {
-                plugins.sort();
+                self.sort_plugins(&mut plugins);
                 plugins
             }
             false => {
                 let matcher = SkimMatcherV2::default();
                 let mut results: Vec<_> = plugins
-                    .iter()
+                    .into_iter()
                     .filter_map(|item| {
-                        matcher
-                            .fuzzy_match(item, &self.search_string)
+                        self.search_score(&matcher, &item)
                             .map(|score| (item, score))
                     })
                     .collect();
 
-                results.sort_by(|a, b| b.1.cmp(&a.1));
+                results.sort_by_key(|(_, score)| Reverse(*score));
 
-                results
-                    .into_iter()
-                    .map(|(item, _)| item.to_string())
-                    .collect()
+                results.into_iter().map(|(item, _)| item).collect()
             }
         }
     }
 
+    pub fn get_installed_plugins(&self) -> Vec<String> {
+        let plugins: Vec<_> = match self.tab.plugins() {
+            None | Some(TmuxPlugins::All) => self.all_installed_plugins.keys().cloned().collect(),
+            Some(tmux_plugins) => tmux_plugins
+                .all()
+                .into_iter()
+                .filter(|p| self.all_installed_plugins.contains_key(p))
+                .collect(),
+        };
+
+        self.filter_by_search(plugins)
+    }
+
     pub fn get_available_plugins(&self) -> Vec<String> {
-        let mut plugins = match self.tab {
-            WindowTab::All => Vec::new(),
-            WindowTab::Themes => self.available_themes.keys().cloned().collect(),
-            WindowTab::StatusBar => self.available_status_bars.keys().cloned().collect(),
-            WindowTab::Plugins => self.available_plugins.keys().cloned().collect(),
+        let plugins: Vec<_> = match self.tab.plugins() {
+            None => Vec::new(),
+            Some(tmux_plugins) => tmux_plugins
+                .all()
+                .into_iter()
+                .filter(|p| !self.all_installed_plugins.contains_key(p))
+                .collect(),
         };
 
-        match self.search_string.is_empty() {
-            true => {
-                plugins.sort();
-                plugins
-            }
-            false => {
-                let matcher = SkimMatcherV2::default();
-                let mut results: Vec<_> = plugins
-                    .iter()
-                    .filter_map(|item| {
-                        matcher
-                            .fuzzy_match(item, &self.search_string)
-                            .map(|score| (item, score))
-                    })
-                    .collect();
+        self.filter_by_search(plugins)
+    }
 
-                results.sort_by(|a, b| b.1.cmp(&a.1));
+    pub fn selected_plugin(&self) -> Option<String> {
+        let (plugins, index) = match self.toggle_available_list {
+            true => (
+                self.get_available_plugins(),
+                self.selected_available_plugin_index,
+            ),
+            false => (
+                self.get_installed_plugins(),
+                self.selected_installed_plugin_index,
+            ),
+        };
+        plugins.get(index).cloned()
+    }
 
-                results
-                    .into_iter()
-                    .map(|(item, _)| item.to_string())
-                    .collect()
+    pub fn sync_readme(&mut self) {
+        let plugin = self.selected_plugin().unwrap_or_default();
+        let installed = self.all_installed_plugins.contains_key(&plugin);
+        if plugin != self.readme.plugin || installed != self.readme.installed {
+            self.readme = Readme::load(&plugin, installed);
+            self.readme_scroll = 0;
+        }
+    }
+
+    pub fn scroll_readme_down(&mut self) {
+        self.readme_scroll = self.readme_scroll.saturating_add(1);
+    }
+
+    pub fn scroll_readme_up(&mut self) {
+        self.readme_scroll = self.readme_scroll.saturating_sub(1);
+    }
+
+    pub fn can_preview(&self) -> bool {
+        self.toggle_available_list && self.selected_plugin().is_some_and(|p| is_theme(&p))
+    }
+
+    pub async fn preview_theme(&mut self) {
+        let Some(plugin) = self.selected_plugin().filter(|p| is_theme(p)) else {
+            return;
+        };
+        self.end_preview();
+        match ThemePreview::start(&plugin).await {
+            Ok(preview) => {
+                self.notify(true, format!("Previewing {plugin}"));
+                self.preview = Some(preview);
+            }
+            Err(e) => self.notify(false, format!("Failed to preview {plugin}: {e}")),
+        }
+    }
+
+    pub fn end_preview(&mut self) {
+        if let Some(preview) = self.preview.take() {
+            let plugin = preview.plugin.clone();
+            match preview.revert() {
+                Ok(()) => self.notify(true, format!("Reverted preview of {plugin}")),
+                Err(e) => self.notify(false, format!("Failed to revert {plugin}: {e}")),
             }
         }
     }
 
+    // A preview only lasts while its theme stays selected.
+    pub fn sync_preview(&mut self) {
+        let selected = match self.toggle_available_list {
+            true => self.selected_plugin(),
+            false => None,
+        };
+        if self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| selected.as_ref() != Some(&preview.plugin))
+        {
+            self.end_preview();
+        }
+    }
+
     pub fn set_tab(&mut self, tab: WindowTab) {
         self.tab = tab;
         self.toggle_available_list = false;
+        if self.tab == WindowTab::Config {
+            self.reload_config_files();
+        }
+    }
+
+    pub fn reload_config_files(&mut self) {
+        self.config_files = config_files(&Path::Tmuxedo.get());
+        let index = self
+            .selected_config_index
+            .min(self.config_files.len().saturating_sub(1));
+        self.select_config_file(index);
+    }
+
+    pub fn selected_config_file(&self) -> Option<&PathBuf> {
+        self.config_files.get(self.selected_config_index)
+    }
+
+    pub fn select_config_file(&mut self, index: usize) -> bool {
+        let Some(file) = self.config_files.get(index) else {
+            self.config_text = None;
+            return false;
+        };
+        self.config_text = fs::read_to_string(file).ok();
+        self.selected_config_index = index;
+        self.config_scroll = 0;
+        true
+    }
+
+    pub fn next_config_file(&mut self) {
+        self.select_config_file(self.selected_config_index + 1);
+    }
+
+    pub fn previous_config_file(&mut self) {
+        if self.selected_config_index != 0 {
+            self.select_config_file(self.selected_config_index - 1);
+        }
+    }
+
+    pub fn scroll_config_down(&mut self) {
+        self.config_scroll = self.config_scroll.saturating_add(1);
+    }
+
+    pub fn scroll_config_up(&mut self) {
+        self.config_scroll = self.config_scroll.saturating_sub(1);
+    }
+
+    // The TUI loop owns the terminal, so it opens the editor on its next turn.
+    pub fn edit_config_file(&mut self) {
+        self.pending_edit = self.selected_config_file().cloned();
+    }
+
+    pub fn finish_edit(&mut self, file: PathBuf, status: io::Result<ExitStatus>) {
+        let name = config_file_name(&file);
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                self.notify(
+                    false,
+                    format!("Editor exited with {status}, {name} not sourced"),
+                );
+                return;
+            }
+            Err(e) => {
+                self.notify(false, format!("Failed to run the editor: {e}"));
+                return;
+            }
+        }
+
+        self.reload_config_files();
+        if let Err(errors) = check_config_file(&file) {
+            for error in errors {
+                log::error(&format!("source-file {error} (not sourced)"));
+                self.notify(false, format!("{error} (not sourced)"));
+            }
+            return;
+        }
+        if TmuxCommand::SourceFile.run(vec![file.display().to_string()]) {
+            self.notify(true, format!("Sourced {name}"));
+        }
+    }
+
+    pub fn start_name_input(&mut self, input: NameInput) {
+        // tmuxedo.conf holds tmuxedo's own options and is recreated on the
+        // next start if it goes missing, so it keeps its name.
+        if matches!(&input, NameInput::Rename(file) if *file == Path::TmuxedoConfig.get()) {
+            self.notify(false, String::from("tmuxedo.conf is reserved by tmuxedo"));
+            return;
+        }
+        self.name_string = match &input {
+            NameInput::Create => String::new(),
+            NameInput::Rename(file) => config_file_name(file),
+        };
+        self.name_error = None;
+        self.name_input = Some(input);
+    }
+
+    pub fn cancel_name_input(&mut self) {
+        self.name_input = None;
+        self.name_string = String::new();
+        self.name_error = None;
+    }
+
+    pub fn push_letter_to_name_string(&mut self, ch: char) {
+        self.name_string.push(ch);
+        self.name_error = None;
+    }
+
+    pub fn pop_letter_from_name_string(&mut self) {
+        self.name_string.pop();
+        self.name_error = None;
+    }
+
+    pub fn confirm_name_input(&mut self) {
+        let path = match new_config_file_path(&self.name_string) {
+            Ok(path) => path,
+            Err(e) => {
+                self.name_error = Some(e);
+                return;
+            }
+        };
+        let name = config_file_name(&path);
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| match &self.name_input {
+                Some(NameInput::Rename(file)) => fs::rename(file, &path),
+                _ => fs::File::create_new(&path).map(|_| ()),
+            });
+        if let Err(e) = result {
+            self.name_error = Some(format!("Failed to write {name}: {e}"));
+            return;
+        }
+
+        match self.name_input.take() {
+            Some(NameInput::Rename(file)) => self.notify(
+                true,
+                format!("Renamed {} to {name}", config_file_name(&file)),
+            ),
+            _ => {
+                self.notify(true, format!("Created {name}"));
+                self.pending_edit = Some(path.clone());
+            }
+        }
+        self.cancel_name_input();
+        self.reload_config_files();
+        if let Some(index) = self.config_files.iter().position(|f| *f == path) {
+            self.select_config_file(index);
+        }
     }
 
     pub fn toggle_available(&mut self) {
@@ -242,60 +576,193 @@ impl State {
         self.search_mode = !self.search_mode;
     }
 
+    pub fn toggle_add_mode(&mut self) {
+        self.add_mode = !self.add_mode;
+        self.add_string = String::new();
+        self.add_error = None;
+    }
+
+    pub fn notify(&mut self, success: bool, message: String) {
+        self.notifications.push(Notification {
+            success,
+            message,
+            at: Instant::now(),
+        });
+        let skip = self.notifications.len().saturating_sub(MAX_NOTIFICATIONS);
+        self.notifications.drain(..skip);
+    }
+
+    // Picks up diagnostics reported while the TUI has the terminal.
+    pub fn collect_reports(&mut self) {
+        for message in log::take_captured() {
+            self.notify(false, message);
+        }
+    }
+
+    pub fn recent_notifications(&self) -> impl Iterator<Item = &Notification> {
+        self.notifications
+            .iter()
+            .filter(|n| n.at.elapsed() <= NOTIFICATION_TTL)
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    pub fn scroll_help_down(&mut self) {
+        self.help_scroll = (self.help_scroll + 1).min(self.help_max_scroll);
+    }
+
+    pub fn scroll_help_up(&mut self) {
+        self.help_scroll = self.help_scroll.saturating_sub(1);
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.show_log = !self.show_log;
+        self.log_entries = match self.show_log {
+            true => log::recent(LOG_ENTRIES),
+            false => Vec::new(),
+        };
+    }
+
     pub fn push_letter_to_search_string(&mut self, ch: char) {
         self.search_string.push(ch);
+        self.keep_selection();
     }
 
     pub fn pop_letter_from_search_string(&mut self) {
         self.search_string.pop();
+        self.keep_selection();
     }
 
     pub fn clear_search_string(&mut self) {
         self.search_string = String::new();
+        self.keep_selection();
     }
 
-    pub fn next_available_plugin(&mut self) {
+    // Follows the selected plugins as the lists change under a live search,
+    // falling back to the first row once they drop out of a list.
+    fn keep_selection(&mut self) {
+        let installed_plugins = self.get_installed_plugins();
+        match installed_plugins
+            .iter()
+            .position(|p| *p == self.selected_installed_plugin_value)
+        {
+            Some(index) => self.selected_installed_plugin_index = index,
+            None => self.reset_selected_installed_plugin(),
+        }
+
         let available_plugins = self.get_available_plugins();
+        match available_plugins
+            .iter()
+            .position(|p| *p == self.selected_available_plugin_value)
+        {
+            Some(index) => self.selected_available_plugin_index = index,
+            None => self.reset_selected_available_plugin(),
+        }
+    }
 
-        if !available_plugins.is_empty() {
-            let n_plugins = available_plugins.len();
-            if self.selected_available_plugin_index != n_plugins - 1 {
-                self.selected_available_plugin_index += 1;
-                self.selected_available_plugin_value =
-                    available_plugins[self.selected_available_plugin_index].clone();
-            }
+    // Positions of the characters in `plugin` matched by the search, for
+    // highlighting. Plugins found through a tag have none.
+    pub fn match_indices(&self, plugin: &str) -> Vec<usize> {
+        match self.search_string.is_empty() {
+            true => Vec::new(),
+            false => SkimMatcherV2::default()
+                .fuzzy_indices(plugin, &self.search_string)
+                .map(|(_, indices)| indices)
+                .unwrap_or_default(),
         }
     }
 
+    pub fn push_letter_to_add_string(&mut self, ch: char) {
+        self.add_string.push(ch);
+        self.add_error = None;
+    }
+
+    pub fn pop_letter_from_add_string(&mut self) {
+        self.add_string.pop();
+        self.add_error = None;
+    }
+
+    // Filters and searches can shrink a list under the selection, so the new
+    // index is clamped to the list rather than trusted.
+    pub fn next_available_plugin(&mut self) {
+        let index = self.selected_available_plugin_index + 1;
+        self.move_available_selection(index);
+    }
+
     pub fn previous_available_plugin(&mut self) {
-        if self.selected_available_plugin_index != 0 {
-            self.selected_available_plugin_index -= 1;
-            self.selected_available_plugin_value =
-                self.get_available_plugins()[self.selected_available_plugin_index].clone();
+        let index = self.selected_available_plugin_index.saturating_sub(1);
+        self.move_available_selection(index);
+    }
+
+    fn move_available_selection(&mut self, index: usize) {
+        let available_plugins = self.get_available_plugins();
+        let index = index.min(available_plugins.len().saturating_sub(1));
+        if let Some(plugin) = available_plugins.get(index) {
+            self.selected_available_plugin_index = index;
+            self.selected_available_plugin_value = plugin.clone();
         }
     }
 
     pub fn next_installed_plugin(&mut self) {
+        let index = self.selected_installed_plugin_index + 1;
+        self.move_installed_selection(index);
+    }
+
+    pub fn previous_installed_plugin(&mut self) {
+        let index = self.selected_installed_plugin_index.saturating_sub(1);
+        self.move_installed_selection(index);
+    }
+
+    fn move_installed_selection(&mut self, index: usize) {
         let installed_plugins = self.get_installed_plugins();
+        let index = index.min(installed_plugins.len().saturating_sub(1));
+        if let Some(plugin) = installed_plugins.get(index) {
+            self.selected_installed_plugin_index = index;
+            self.selected_installed_plugin_value = plugin.clone();
+        }
+    }
 
-        if !installed_plugins.is_empty() {
-            let n_plugins = installed_plugins.len();
-            if self.selected_installed_plugin_index != n_plugins - 1 {
-                self.selected_installed_plugin_index += 1;
-                self.selected_installed_plugin_value =
-                    installed_plugins[self.selected_installed_plugin_index].clone();
+    pub fn select_available_plugin(&mut self, index: usize) -> bool {
+        match self.get_available_plugins().get(index) {
+            Some(plugin) => {
+                self.toggle_available_list = true;
+                self.selected_available_plugin_index = index;
+                self.selected_available_plugin_value = plugin.clone();
+                true
             }
+            None => false,
         }
     }
 
-    pub fn previous_installed_plugin(&mut self) {
-        if self.selected_installed_plugin_index != 0 {
-            self.selected_installed_plugin_index -= 1;
-            self.selected_installed_plugin_value =
-                self.get_installed_plugins()[self.selected_installed_plugin_index].clone();
+    pub fn select_installed_plugin(&mut self, index: usize) -> bool {
+        match self.get_installed_plugins().get(index) {
+            Some(plugin) => {
+                self.toggle_available_list = false;
+                self.selected_installed_plugin_index = index;
+                self.selected_installed_plugin_value = plugin.clone();
+                true
+            }
+            None => false,
         }
     }
 
+    // Returns whether this click lands on the same cell as the previous one
+    // within the double-click interval.
+    pub fn register_click(&mut self, column: u16, row: u16) -> bool {
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(at, c, r)| {
+            c == column && r == row && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        });
+        self.last_click = match double {
+            true => None,
+            false => Some((now, column, row)),
+        };
+        double
+    }
+
     pub fn reset_selected_available_plugin(&mut self) {
         let available_plugins = self.get_available_plugins();
         self.selected_available_plugin_index = 0;
@@ -314,116 +781,441 @@ impl State {
         };
     }
 
-    fn get_installed_plugin_dir_name(&self) -> Result<String, Box<dyn Error>> {
-        let plugins = self.get_installed_plugins();
-        let dir_name = format_plugin_dir_name(&plugins[self.selected_installed_plugin_index]);
-        Ok(dir_name)
+    fn save_manifest(&mut self) {
+        if let Err(e) = self.write_installed_plugins() {
+            self.notify(false, format!("Failed to write plugins.conf: {e}"));
+        }
+    }
+
+    fn write_installed_plugins(&self) -> io::Result<()> {
+        let path = Path::PluginsConfig.get();
+        let mut file = OpenOptions::new().write(true).truncate(true).open(path)?;
+
+        let mut plugins: Vec<&Plugin> = self.all_installed_plugins.values().collect();
+        plugins.sort_by(|a, b| a.path.cmp(&b.path));
+        for plugin in plugins {
+            if plugin.disabled {
+                write!(file, "{DISABLED_PREFIX} ")?;
+            }
+            match &plugin.branch {
+                Some(branch) => writeln!(file, "{} {branch}", plugin.path)?,
+                None => writeln!(file, "{}", plugin.path)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    // Bulk actions apply to the marked plugins in the focused list, or to the
+    // selected plugin when nothing there is marked.
+    pub fn targets(&self) -> Vec<String> {
+        let plugins = match self.toggle_available_list {
+            true => self.get_available_plugins(),
+            false => self.get_installed_plugins(),
+        };
+        let marked: Vec<String> = plugins
+            .into_iter()
+            .filter(|p| self.marked.contains(p))
+            .collect();
+        match marked.is_empty() {
+            true => self.selected_plugin().into_iter().collect(),
+            false => marked,
+        }
+    }
+
+    pub fn toggle_mark(&mut self) {
+        if let Some(plugin) = self.selected_plugin()
+            && !self.marked.remove(&plugin)
+        {
+            self.marked.insert(plugin);
+        }
     }
 
-    fn move_plugin_to_available_from_all_tab(&mut self, plugin: &str) {
-        if let Some(p) = self.installed_themes.remove(plugin) {
-            self.available_themes.insert(plugin.to_string(), p.clone());
-            self.all_installed_plugins.remove(plugin);
-        } else if let Some(p) = self.installed_status_bars.remove(plugin) {
-            self.available_status_bars
-                .insert(plugin.to_string(), p.clone());
-            self.all_installed_plugins.remove(plugin);
-        } else if let Some(p) = self.installed_plugins.remove(plugin) {
-            self.available_plugins.insert(plugin.to_string(), p.clone());
-            self.all_installed_plugins.remove(plugin);
+    pub fn toggle_mark_all(&mut self) {
+        let plugins = match self.toggle_available_list {
+            true => self.get_available_plugins(),
+            false => self.get_installed_plugins(),
+        };
+        match plugins.iter().all(|p| self.marked.contains(p)) {
+            true => plugins.iter().for_each(|p| {
+                self.marked.remove(p);
+            }),
+            false => self.marked.extend(plugins),
         }
     }
 
-    fn move_plugin_to_installed(&mut self, plugin: &str) {
-        match self.tab {
-            WindowTab::Themes => {
-                if let Some(p) = self.available_themes.remove(plugin) {
-                    self.installed_themes.insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.insert(plugin.to_string(), p);
+    pub async fn install_plugins(&mut self, plugins: Vec<String>) {
+        let mut installed = vec![];
+        let mut handles = vec![];
+        for plugin in plugins {
+            handles.push(task::spawn(async move {
+                let status = git_clone(&plugin, None).await;
+                (plugin, status)
+            }));
+        }
+        for handle in handles {
+            match handle.await {
+                Ok((plugin, Ok(status))) if status.success() => {
+                    self.notify(true, format!("Installed {plugin}"));
+                    self.marked.remove(&plugin);
+                    installed.push(plugin.clone());
+                    self.all_installed_plugins
+                        .insert(plugin.clone(), Plugin::new(plugin, None));
                 }
-            }
-            WindowTab::StatusBar => {
-                if let Some(p) = self.available_status_bars.remove(plugin) {
-                    self.installed_status_bars
-                        .insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.insert(plugin.to_string(), p);
+                Ok((plugin, Err(e))) => {
+                    self.notify(false, format!("Failed to install {plugin}: {e}"))
                 }
+                Ok(_) => {}
+                Err(join_err) => log::report(&format!("Task join error: {join_err}")),
             }
-            WindowTab::Plugins => {
-                if let Some(p) = self.available_plugins.remove(plugin) {
-                    self.installed_plugins.insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.insert(plugin.to_string(), p);
-                }
+        }
+
+        if let Some(preview) = self
+            .preview
+            .take_if(|preview| self.all_installed_plugins.contains_key(&preview.plugin))
+        {
+            preview.discard();
+        }
+        self.record(UndoAction::Install(installed));
+        self.save_manifest();
+        self.find_stale_plugins();
+        self.load_plugin_dates();
+        if self.selected_available_plugin_index >= self.get_available_plugins().len() {
+            self.reset_selected_available_plugin();
+        }
+        run_plugins().await;
+    }
+
+    pub async fn add_plugin(&mut self) {
+        let mut words = self.add_string.split_whitespace();
+        let (Some(plugin), branch, None) = (words.next(), words.next(), words.next()) else {
+            self.add_error = Some(String::from("Expected owner/repo or a URL, then a branch"));
+            return;
+        };
+        let plugin = normalize_plugin(plugin).to_string();
+        let branch = branch.map(String::from);
+
+        let dir_name = format_plugin_dir_name(&plugin);
+        if self
+            .all_installed_plugins
+            .keys()
+            .any(|p| format_plugin_dir_name(p) == dir_name)
+        {
+            self.add_error = Some(format!("{plugin} is already installed"));
+            return;
+        }
+        if let Err(e) = validate_plugin(&plugin, branch.as_deref()).await {
+            self.add_error = Some(e);
+            return;
+        }
+
+        match git_clone(&plugin, branch.clone()).await {
+            Ok(status) if status.success() => {}
+            _ => {
+                self.add_error = Some(format!("Failed to clone {plugin}"));
+                return;
             }
-            _ => todo!(),
         }
+        self.all_installed_plugins
+            .insert(plugin.clone(), Plugin::new(plugin.clone(), branch));
+        self.save_manifest();
+        self.find_stale_plugins();
+        self.load_plugin_dates();
+        self.toggle_add_mode();
+
+        self.set_tab(WindowTab::All);
+        self.clear_search_string();
+        let installed_plugins = self.get_installed_plugins();
+        self.selected_installed_plugin_index = installed_plugins
+            .iter()
+            .position(|p| *p == plugin)
+            .unwrap_or_default();
+        self.notify(true, format!("Added {plugin}"));
+        self.record(UndoAction::Install(vec![plugin.clone()]));
+        self.selected_installed_plugin_value = plugin;
+        run_plugins().await;
     }
 
-    fn move_plugin_to_available(&mut self, plugin: &str) {
-        match self.tab {
-            WindowTab::Themes => {
-                if let Some(p) = self.installed_themes.remove(plugin) {
-                    self.available_themes.insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.remove(plugin);
+    pub async fn update_plugins(&mut self, plugins: Vec<String>) {
+        let mut handles = vec![];
+        for plugin in plugins {
+            handles.push(task::spawn(async move {
+                let status = git_pull(&format_plugin_dir_name(&plugin)).await;
+                (plugin, status)
+            }));
+        }
+        for handle in handles {
+            match handle.await {
+                Ok((plugin, Ok(status))) if status.success() => {
+                    self.notify(true, format!("Updated {plugin}"));
+                    self.marked.remove(&plugin);
+                    if let Some(val) = self.all_installed_plugins.get_mut(&plugin) {
+                        val.set_commit_hash(String::new());
+                    }
                 }
-            }
-            WindowTab::StatusBar => {
-                if let Some(p) = self.installed_status_bars.remove(plugin) {
-                    self.available_status_bars
-                        .insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.remove(plugin);
+                Ok((plugin, Err(e))) => {
+                    self.notify(false, format!("Failed to update {plugin}: {e}"))
                 }
+                Ok(_) => {}
+                Err(join_err) => log::report(&format!("Task join error: {join_err}")),
+            }
+        }
+
+        self.find_stale_plugins();
+        self.load_plugin_dates();
+        if self.selected_installed_plugin_index >= self.get_installed_plugins().len() {
+            self.reset_selected_installed_plugin();
+        }
+        run_plugins().await;
+    }
+
+    pub async fn remove_plugins(&mut self, plugins: Vec<String>) {
+        let mut removed = vec![];
+        for plugin in plugins {
+            let dir_name = format_plugin_dir_name(&plugin);
+            let commit = git_head(&dir_name).await;
+            match remove_dir(dir_name) {
+                Ok(()) => self.notify(true, format!("Removed {plugin}")),
+                Err(e) => self.notify(false, format!("Removed {plugin}, but not its clone: {e}")),
+            }
+            if let Some(removed_plugin) = self.all_installed_plugins.remove(&plugin) {
+                removed.push(RemovedPlugin {
+                    plugin: removed_plugin,
+                    commit,
+                });
+            }
+            self.stale_plugins.remove(&plugin);
+            self.marked.remove(&plugin);
+        }
+        self.record(UndoAction::Remove(removed));
+        self.save_manifest();
+        if self.selected_installed_plugin_index >= self.get_installed_plugins().len() {
+            self.reset_selected_installed_plugin();
+        }
+        run_plugins().await;
+    }
+
+    pub async fn toggle_disabled(&mut self, plugins: Vec<String>) {
+        let mut toggled = vec![];
+        for plugin in plugins {
+            if let Some(val) = self.all_installed_plugins.get_mut(&plugin) {
+                val.disabled = !val.disabled;
+                let message = match val.disabled {
+                    true => format!("Disabled {plugin}"),
+                    false => format!("Enabled {plugin}"),
+                };
+                self.notify(true, message);
+                toggled.push(plugin.clone());
+            }
+            self.marked.remove(&plugin);
+        }
+        self.record(UndoAction::ToggleDisabled(toggled));
+        self.save_manifest();
+        if self.selected_installed_plugin_index >= self.get_installed_plugins().len() {
+            self.reset_selected_installed_plugin();
+        }
+        run_plugins().await;
+    }
+
+    fn record(&mut self, action: UndoAction) {
+        if self.undoing || action.is_empty() {
+            return;
+        }
+        self.undo_stack.push(action);
+        let skip = self.undo_stack.len().saturating_sub(UNDO_LIMIT);
+        self.undo_stack.drain(..skip);
+    }
+
+    pub async fn undo(&mut self) {
+        let Some(action) = self.undo_stack.pop() else {
+            self.notify(false, String::from("Nothing to undo"));
+            return;
+        };
+        self.notify(true, format!("Undoing {action}"));
+
+        // Undoing an install deletes clones, so it is confirmed like any other
+        // removal and recorded so that it can be undone in turn.
+        if let UndoAction::Install(plugins) = action {
+            self.request(PendingAction::UndoInstall(plugins)).await;
+            return;
+        }
+
+        self.undoing = true;
+        match action {
+            UndoAction::Install(_) => {}
+            UndoAction::Remove(removed) => self.restore_plugins(removed).await,
+            UndoAction::ToggleDisabled(plugins) => self.toggle_disabled(plugins).await,
+        }
+        self.undoing = false;
+    }
+
+    // Clones removed plugins again on their branch and moves them back to the
+    // commit they were at, with their plugins.conf line as it was. A clone
+    // that could not be deleted is reused. Plugins that can't be cloned stay
+    // on the stack so the undo can be retried.
+    async fn restore_plugins(&mut self, removed: Vec<RemovedPlugin>) {
+        let mut failed = vec![];
+        for RemovedPlugin { mut plugin, commit } in removed {
+            let name = plugin.path.clone();
+            let dir_name = format_plugin_dir_name(&name);
+            let cloned = Path::Plugins.get().join(&dir_name).is_dir()
+                || git_clone(&name, plugin.branch.clone())
+                    .await
+                    .is_ok_and(|status| status.success());
+            if !cloned {
+                self.collect_reports();
+                self.notify(false, format!("Failed to restore {name}"));
+                failed.push(RemovedPlugin { plugin, commit });
+                continue;
             }
-            WindowTab::Plugins => {
-                if let Some(p) = self.installed_plugins.remove(plugin) {
-                    self.available_plugins.insert(plugin.to_string(), p.clone());
-                    self.all_installed_plugins.remove(plugin);
+            match commit {
+                Some(commit)
+                    if !git_reset(&dir_name, &commit)
+                        .await
+                        .is_ok_and(|status| status.success()) =>
+                {
+                    self.notify(false, format!("Restored {name}, but not at {commit}"))
                 }
+                _ => self.notify(true, format!("Restored {name}")),
             }
-            WindowTab::All => self.move_plugin_to_available_from_all_tab(plugin),
+            plugin.set_commit_hash(String::new());
+            self.all_installed_plugins.insert(name, plugin);
         }
+        if !failed.is_empty() {
+            self.undo_stack.push(UndoAction::Remove(failed));
+        }
+
+        self.save_manifest();
+        self.find_stale_plugins();
+        self.load_plugin_dates();
+        if self.selected_available_plugin_index >= self.get_available_plugins().len() {
+            self.reset_selected_available_plugin();
+        }
+        run_plugins().await;
     }
 
-    fn write_installed_plugins(&self) -> io::Result<()> {
-        let path = Path::PluginsConfig.get();
-        let mut file = OpenOptions::new().write(true).truncate(true).open(path)?;
-        for plugin in &self.all_installed_plugins {
-            writeln!(file, "{}", String::from(plugin.0))?;
+    // Destructive actions wait for a yes/no answer unless `@tmuxedo-confirm` is off.
+    pub async fn request(&mut self, action: PendingAction) {
+        match Config::load().confirm() {
+            true => self.pending_action = Some(action),
+            false => self.run_action(action).await,
         }
+    }
 
-        Ok(())
+    pub async fn confirm_pending_action(&mut self) {
+        if let Some(action) = self.pending_action.take() {
+            self.run_action(action).await;
+        }
     }
 
-    pub async fn install_plugin(&mut self) {
-        let plugins = self.get_available_plugins();
-        let plugin = &plugins[self.selected_available_plugin_index];
+    // A cancelled undo goes back on the stack so that it can be retried.
+    pub fn cancel_pending_action(&mut self) {
+        if let Some(PendingAction::UndoInstall(plugins)) = self.pending_action.take() {
+            self.undo_stack.push(UndoAction::Install(plugins));
+        }
+    }
 
-        let status = git_clone(plugin, None).await.expect("REASON");
-        if status.success() {
-            self.move_plugin_to_installed(plugin);
-            let _ = self.write_installed_plugins();
-            run_plugins();
+    async fn run_action(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::Remove(plugins) | PendingAction::UndoInstall(plugins) => {
+                self.remove_plugins(plugins).await
+            }
         }
     }
+}
 
-    pub async fn update_plugin(&mut self) {
-        let plugin: String = self.get_installed_plugin_dir_name().expect("REASON");
-        let status = git_pull(&plugin).await.expect("REASON");
-        if status.success() {
-            if let Some(val) = self.all_installed_plugins.get_mut(&plugin) {
-                val.set_commit_hash(String::new());
+pub struct RemovedPlugin {
+    plugin: Plugin,
+    commit: Option<String>,
+}
+
+// Each entry reverses one TUI action, newest last.
+pub enum UndoAction {
+    Install(Vec<String>),
+    Remove(Vec<RemovedPlugin>),
+    ToggleDisabled(Vec<String>),
+}
+
+impl UndoAction {
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Install(plugins) | Self::ToggleDisabled(plugins) => plugins.is_empty(),
+            Self::Remove(removed) => removed.is_empty(),
+        }
+    }
+}
+
+impl Display for UndoAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Install(plugins) => write!(f, "install of {}", plugins.join(", ")),
+            Self::Remove(removed) => {
+                let plugins: Vec<&str> = removed.iter().map(|r| r.plugin.path.as_str()).collect();
+                write!(f, "removal of {}", plugins.join(", "))
+            }
+            Self::ToggleDisabled(plugins) => {
+                write!(f, "disable/enable of {}", plugins.join(", "))
             }
-            run_plugins();
         }
     }
+}
+
+pub enum NameInput {
+    Create,
+    Rename(PathBuf),
+}
+
+// Names are relative to the tmuxedo directory and get a `.conf` extension
+// unless they have one.
+fn new_config_file_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    let relative = std::path::Path::new(name);
+    if name.is_empty() {
+        return Err(String::from("Enter a file name"));
+    }
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(String::from("Use a path inside the tmuxedo directory"));
+    }
 
-    pub fn remove_plugin(&mut self) {
-        let plugins = self.get_installed_plugins();
-        let plugin = &plugins[self.selected_installed_plugin_index];
+    let relative = match relative.extension() {
+        Some(_) => relative.to_path_buf(),
+        None => relative.with_extension("conf"),
+    };
+    let path = Path::Tmuxedo.get().join(relative);
+    if is_data_file(&path) || path == Path::TmuxedoConfig.get() {
+        return Err(format!("{name} is reserved by tmuxedo"));
+    }
+    if path.exists() {
+        return Err(format!("{} already exists", config_file_name(&path)));
+    }
+    Ok(path)
+}
 
-        let _ = remove_dir(self.get_installed_plugin_dir_name().expect("REASON"));
-        self.move_plugin_to_available(plugin);
-        let _ = self.write_installed_plugins();
-        run_plugins();
+pub enum PendingAction {
+    Remove(Vec<String>),
+    UndoInstall(Vec<String>),
+}
+
+impl PendingAction {
+    // The first line asks, the last one warns, and a bulk removal lists the
+    // plugins one per line in between.
+    pub fn prompt(&self) -> Vec<String> {
+        match self {
+            Self::Remove(plugins) | Self::UndoInstall(plugins) if plugins.len() == 1 => vec![
+                format!("Remove {}?", plugins[0]),
+                String::from("Its clone and plugins.conf line are deleted."),
+            ],
+            Self::Remove(plugins) | Self::UndoInstall(plugins) => {
+                let mut lines = vec![format!("Remove {} plugins?", plugins.len())];
+                lines.extend(plugins.iter().map(|plugin| format!("  {plugin}")));
+                lines.push(String::from(
+                    "Their clones and plugins.conf lines are deleted.",
+                ));
+                lines
+            }
+        }
     }
 }
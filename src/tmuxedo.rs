@@ -1,6 +1,13 @@
+use crate::log;
 use crate::plugins::{clone, pull};
+use crate::profile::Timing;
 use dirs::home_dir;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
 use std::io::{self, Write};
+use std::process::Command;
+use std::time::Instant;
 use std::vec;
 use std::{
     fs::{self, OpenOptions},
@@ -10,12 +17,23 @@ use walkdir::WalkDir;
 
 use crate::TmuxCommand;
 
+const CHECK_SOCKET: &str = "tmuxedo-check";
+const DATA_FILES: [&str; 2] = ["plugins.conf", "registry.ini"];
+
 pub enum Path {
     Tmuxedo,
     Plugins,
+    Registries,
     PluginsConfig,
     TmuxedoConfig,
     TmuxConfig,
+    UserRegistry,
+    State,
+    Log,
+    Cache,
+    RegistryCache,
+    Readmes,
+    Previews,
 }
 
 impl Path {
@@ -24,37 +42,227 @@ impl Path {
         match self {
             Self::Tmuxedo => path.push(".config/tmux/tmuxedo"),
             Self::Plugins => path.push(".local/share/tmuxedo/plugins"),
+            Self::Registries => path.push(".local/share/tmuxedo/registries"),
             Self::PluginsConfig => path.push(".config/tmux/tmuxedo/plugins.conf"),
             Self::TmuxedoConfig => path.push(".config/tmux/tmuxedo/tmuxedo.conf"),
             Self::TmuxConfig => path.push(".config/tmux/tmux.conf"),
+            Self::UserRegistry => path.push(".config/tmux/tmuxedo/registry.ini"),
+            Self::State => path.push(".local/state/tmuxedo"),
+            Self::Log => path.push(".local/state/tmuxedo/tmuxedo.log"),
+            Self::Cache => path.push(".cache/tmuxedo"),
+            Self::RegistryCache => path.push(".cache/tmuxedo/registry.ini"),
+            Self::Readmes => path.push(".cache/tmuxedo/readmes"),
+            Self::Previews => path.push(".cache/tmuxedo/previews"),
         };
         path
     }
 }
 
-pub async fn source_all_tmuxedo_files(update: bool) {
+pub async fn source_all_tmuxedo_files(update: bool) -> Vec<Timing> {
     let tmuxedo_dir = Path::Tmuxedo.get();
+    let mut errors = check_config_files(&config_files(&tmuxedo_dir));
+    let mut timings = vec![];
     for entry in WalkDir::new(&tmuxedo_dir)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
         .filter(|e| e.path().is_file())
     {
-        if entry.path().display().to_string().ends_with("plugins.conf") {
-            let _ = match update {
-                true => pull().await,
-                false => clone().await,
+        let start = Instant::now();
+        let target = entry.path().display().to_string();
+        if target.ends_with("plugins.conf") {
+            let command = match update {
+                true => {
+                    let _ = pull().await;
+                    "pull"
+                }
+                false => {
+                    let _ = clone().await;
+                    "clone"
+                }
             };
+            timings.push(Timing::new(command, target, start.elapsed()));
+        } else if is_data_file(entry.path()) {
+            continue;
+        } else if let Some(errors) = errors.remove(entry.path()) {
+            for error in errors {
+                log::report(&format!("{error} (not sourced)"));
+                log::error(&format!("source-file {error} (not sourced)"));
+            }
         } else {
-            let arguments = vec![entry.path().display().to_string()];
-            TmuxCommand::SourceFile.run(arguments)
+            TmuxCommand::SourceFile.run(vec![target.clone()]);
+            timings.push(Timing::new(
+                &TmuxCommand::SourceFile.command(),
+                target,
+                start.elapsed(),
+            ));
+        }
+    }
+    timings
+}
+
+// Files are sourced in path order, which is also the order they are listed in.
+pub fn config_files(dir: &std::path::Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.path().is_file())
+        .map(|e| e.path().to_path_buf())
+        .filter(|p| !is_data_file(p))
+        .collect()
+}
+
+pub fn is_data_file(path: &std::path::Path) -> bool {
+    DATA_FILES.iter().any(|name| path.ends_with(name))
+}
+
+pub fn config_file_name(path: &std::path::Path) -> String {
+    let dir = Path::Tmuxedo.get();
+    path.strip_prefix(&dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+pub struct ConfigError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.file, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+// tmux prefixes each error with the file it is in, as `file:line: message`
+// or `file: message` when the file can't be read. Anything else can't be
+// attributed, so it counts against every checked file.
+fn parse_config_errors(paths: &[PathBuf], output: &str) -> HashMap<PathBuf, Vec<ConfigError>> {
+    let re = Regex::new(r"^(.+):(\d+): (.*)$").unwrap();
+    let mut errors: HashMap<PathBuf, Vec<ConfigError>> = HashMap::new();
+    for line in output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let with_line = re.captures(line).and_then(|caps| {
+            let path = paths.iter().find(|p| p.as_os_str() == &caps[1])?;
+            Some((path, caps[2].parse().ok(), caps[3].to_string()))
+        });
+        let found = with_line.or_else(|| {
+            paths.iter().find_map(|path| {
+                let message = line.strip_prefix(&format!("{}: ", path.display()))?;
+                Some((path, None, message.to_string()))
+            })
+        });
+        let matching: Vec<(&PathBuf, Option<usize>, String)> = match found {
+            Some(found) => vec![found],
+            None => paths.iter().map(|p| (p, None, line.to_string())).collect(),
+        };
+        for (path, line, message) in matching {
+            errors.entry(path.clone()).or_default().push(ConfigError {
+                file: path.display().to_string(),
+                line,
+                message,
+            });
         }
     }
+    errors
+}
+
+// A throwaway server on its own socket parses the files without touching the
+// user's server, and exits straight away because it has no sessions. All
+// files go through one server; only the ones with errors are returned. tmux
+// reports relative paths against its working directory, so it is given
+// absolute ones to match its errors against.
+pub fn check_config_files(paths: &[PathBuf]) -> HashMap<PathBuf, Vec<ConfigError>> {
+    if paths.is_empty() {
+        return HashMap::new();
+    }
+    let absolute: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| std::path::absolute(path).unwrap_or_else(|_| path.clone()))
+        .collect();
+
+    let mut errors = match Command::new("tmux")
+        .args(["-L", CHECK_SOCKET, "-f", "/dev/null", "start-server", ";"])
+        .args(["source-file", "-n"])
+        .args(&absolute)
+        .output()
+    {
+        Ok(output) if output.status.success() => HashMap::new(),
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let errors = parse_config_errors(&absolute, &format!("{stdout}{stderr}"));
+            match errors.is_empty() {
+                true => fail_all(&absolute, format!("tmux exited with {}", output.status)),
+                false => errors,
+            }
+        }
+        Err(e) => fail_all(&absolute, format!("Failed to run tmux: {e}")),
+    };
+
+    paths
+        .iter()
+        .zip(&absolute)
+        .filter_map(|(path, absolute)| Some((path.clone(), errors.remove(absolute)?)))
+        .collect()
+}
+
+fn fail_all(paths: &[PathBuf], message: String) -> HashMap<PathBuf, Vec<ConfigError>> {
+    paths
+        .iter()
+        .map(|path| {
+            let error = ConfigError {
+                file: path.display().to_string(),
+                line: None,
+                message: message.clone(),
+            };
+            (path.clone(), vec![error])
+        })
+        .collect()
+}
+
+pub fn check_config_file(path: &std::path::Path) -> Result<(), Vec<ConfigError>> {
+    match check_config_files(&[path.to_path_buf()]).remove(path) {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+pub fn run_check(dir: Option<PathBuf>) -> bool {
+    let dir = dir.unwrap_or_else(|| Path::Tmuxedo.get());
+    let files = config_files(&dir);
+    let mut errors = check_config_files(&files);
+
+    let mut failures = 0;
+    for file in &files {
+        if let Some(errors) = errors.remove(file) {
+            failures += 1;
+            for error in errors {
+                println!("{error}");
+            }
+        }
+    }
+
+    match failures {
+        0 => println!("{} config file(s) OK", files.len()),
+        n => println!("{n} of {} config file(s) have errors", files.len()),
+    }
+    failures == 0
 }
 
 fn ensure_dir_exists(path: &PathBuf) {
     match fs::create_dir_all(path) {
         Ok(_) => {}
-        Err(e) => eprintln!("Error creating directory: {e}"),
+        Err(e) => log::report(&format!("Error creating directory: {e}")),
     }
 }
 
@@ -81,7 +289,61 @@ pub fn ensure_structure() {
     let tmux_defaults: Vec<&str> = vec!["run-shell 'tmuxedo'"];
     ensure_dir_exists(&Path::Tmuxedo.get());
     ensure_dir_exists(&Path::Plugins.get());
+    ensure_dir_exists(&Path::Registries.get());
+    ensure_dir_exists(&Path::State.get());
     let _ = ensure_file_exists(&Path::PluginsConfig.get(), plugins_defaults);
     let _ = ensure_file_exists(&Path::TmuxedoConfig.get(), tmuxedo_defaults);
     let _ = ensure_file_exists(&Path::TmuxConfig.get(), tmux_defaults);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths() -> Vec<PathBuf> {
+        vec![PathBuf::from("/conf/a.conf"), PathBuf::from("/conf/b.conf")]
+    }
+
+    #[test]
+    fn parse_config_errors_groups_errors_by_file() {
+        let output = "/conf/a.conf:1: unknown command: bogus\n\
+                      /conf/b.conf:3: unknown command: foo\n\
+                      /conf/a.conf:7: invalid key: C-\n";
+
+        let errors = parse_config_errors(&paths(), output);
+
+        let a = &errors[&PathBuf::from("/conf/a.conf")];
+        assert_eq!(a.len(), 2);
+        assert_eq!(a[0].line, Some(1));
+        assert_eq!(a[0].message, "unknown command: bogus");
+        assert_eq!(a[1].to_string(), "/conf/a.conf:7: invalid key: C-");
+        let b = &errors[&PathBuf::from("/conf/b.conf")];
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[0].line, Some(3));
+    }
+
+    #[test]
+    fn parse_config_errors_reads_errors_without_a_line() {
+        let errors = parse_config_errors(&paths(), "/conf/b.conf: No such file or directory\n");
+
+        assert_eq!(errors.len(), 1);
+        let b = &errors[&PathBuf::from("/conf/b.conf")];
+        assert_eq!(b[0].line, None);
+        assert_eq!(b[0].message, "No such file or directory");
+    }
+
+    #[test]
+    fn parse_config_errors_blames_every_file_for_unknown_lines() {
+        let errors = parse_config_errors(&paths(), "server exited unexpectedly\n");
+
+        assert_eq!(errors.len(), 2);
+        for path in paths() {
+            assert_eq!(errors[&path][0].message, "server exited unexpectedly");
+        }
+    }
+
+    #[test]
+    fn parse_config_errors_ignores_blank_output() {
+        assert!(parse_config_errors(&paths(), "\n  \n").is_empty());
+    }
+}
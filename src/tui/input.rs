@@ -1,96 +1,348 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 
-use crate::{state::State, tui::WindowTab};
+use crate::{
+    bindings::Binding,
+    state::{NameInput, PendingAction, State},
+    tui::{AppLayout, WindowTab, ui_list::list_row_at, ui_tabs::tab_at},
+};
+
+fn pressed(state: &State, binding: Binding, key: &KeyEvent) -> bool {
+    state.keymap.matches(binding, key)
+}
 
 pub async fn handle_input(key: KeyEvent, state: &mut State) {
+    if state.pending_action.is_some() {
+        handle_confirm_input(key, state).await;
+        return;
+    }
+    if state.show_help {
+        handle_help_input(key, state);
+        return;
+    }
+    if state.show_log {
+        handle_log_input(key, state);
+        return;
+    }
+    if state.add_mode {
+        handle_add_mode_input(key, state).await;
+        return;
+    }
+    if state.name_input.is_some() {
+        handle_name_input(key, state);
+        return;
+    }
     match state.search_mode {
         true => handle_search_mode_input(key, state).await,
         false => handle_normal_mode_input(key, state).await,
     }
 }
 
+async fn handle_add_mode_input(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::CancelAdd, &key) {
+        state.toggle_add_mode();
+    } else if pressed(state, Binding::ConfirmAdd, &key) {
+        state.add_plugin().await;
+    } else if let KeyCode::Backspace = key.code {
+        state.pop_letter_from_add_string();
+    } else if let KeyCode::Char(c) = key.code {
+        state.push_letter_to_add_string(c);
+    }
+}
+
+fn handle_name_input(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::CancelName, &key) {
+        state.cancel_name_input();
+    } else if pressed(state, Binding::ConfirmName, &key) {
+        state.confirm_name_input();
+    } else if let KeyCode::Backspace = key.code {
+        state.pop_letter_from_name_string();
+    } else if let KeyCode::Char(c) = key.code {
+        state.push_letter_to_name_string(c);
+    }
+}
+
 async fn handle_search_mode_input(key: KeyEvent, state: &mut State) {
-    if let KeyCode::Esc = key.code {
+    if pressed(state, Binding::ExitSearch, &key) {
         state.toggle_search_mode();
         state.clear_search_string();
-    }
-    if let KeyCode::Char(c) = key.code {
+    } else if pressed(state, Binding::FindSearch, &key) {
+        state.toggle_search_mode();
+    } else if let KeyCode::Backspace = key.code {
+        state.pop_letter_from_search_string();
+    } else if let KeyCode::Char(c) = key.code {
         state.push_letter_to_search_string(c);
     }
-    if let KeyCode::Backspace = key.code {
-        state.pop_letter_from_search_string();
+}
+
+async fn handle_confirm_input(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::Confirm, &key) {
+        state.confirm_pending_action().await;
+    } else if pressed(state, Binding::Cancel, &key) {
+        state.cancel_pending_action();
     }
-    if let KeyCode::Enter = key.code {
-        state.toggle_search_mode();
-        state.reset_selected_installed_plugin();
-        state.reset_selected_available_plugin();
+}
+
+fn handle_help_input(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::HideHelp, &key) || pressed(state, Binding::ShowHelp, &key) {
+        state.toggle_help();
+    }
+    if pressed(state, Binding::ScrollDown, &key) || pressed(state, Binding::Next, &key) {
+        state.scroll_help_down();
+    }
+    if pressed(state, Binding::ScrollUp, &key) || pressed(state, Binding::Previous, &key) {
+        state.scroll_help_up();
+    }
+}
+
+fn handle_log_input(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::HideLog, &key) || pressed(state, Binding::ShowLog, &key) {
+        state.toggle_log();
     }
 }
 
 async fn handle_normal_mode_input(key: KeyEvent, state: &mut State) {
-    if let KeyCode::Char('1') = key.code {
-        state.set_tab(WindowTab::All);
-        state.reset_selected_available_plugin();
-        state.reset_selected_installed_plugin();
+    if state.preview.is_some() && pressed(state, Binding::CancelPreview, &key) {
+        state.end_preview();
+        return;
     }
-    if let KeyCode::Char('2') = key.code {
-        state.set_tab(WindowTab::Themes);
+    if let KeyCode::Char(c) = key.code
+        && let Some(digit) = c.to_digit(10)
+        && let Some(tab) = WindowTab::tabs()
+            .into_iter()
+            .nth((digit as usize).wrapping_sub(1))
+    {
+        state.set_tab(tab);
         state.reset_selected_available_plugin();
         state.reset_selected_installed_plugin();
     }
-    if let KeyCode::Char('3') = key.code {
-        state.set_tab(WindowTab::StatusBar);
+    if pressed(state, Binding::NextTab, &key) || pressed(state, Binding::PreviousTab, &key) {
+        let tabs = WindowTab::tabs();
+        let index = match pressed(state, Binding::NextTab, &key) {
+            true => (state.tab.index() + 1) % tabs.len(),
+            false => (state.tab.index() + tabs.len() - 1) % tabs.len(),
+        };
+        state.set_tab(tabs[index].clone());
         state.reset_selected_available_plugin();
         state.reset_selected_installed_plugin();
     }
-    if let KeyCode::Char('4') = key.code {
-        state.set_tab(WindowTab::Plugins);
-        state.reset_selected_available_plugin();
-        state.reset_selected_installed_plugin();
+    if state.tab == WindowTab::Config {
+        config_actions(key, state);
+        return;
     }
-    if let (KeyCode::Char('o'), KeyModifiers::CONTROL) = (key.code, key.modifiers)
-        && state.tab != WindowTab::All
-    {
+    if pressed(state, Binding::ToggleAvailable, &key) {
         state.toggle_available();
     }
-    if let KeyCode::Char('/') = key.code {
+    if pressed(state, Binding::Search, &key) {
         state.clear_search_string();
         state.toggle_search_mode();
     }
-    if let KeyCode::Esc = key.code
-        && !state.search_mode
-    {
+    if pressed(state, Binding::ScrollDown, &key) {
+        state.scroll_readme_down();
+    }
+    if pressed(state, Binding::ScrollUp, &key) {
+        state.scroll_readme_up();
+    }
+    if pressed(state, Binding::Add, &key) {
+        state.toggle_add_mode();
+    }
+    if pressed(state, Binding::ShowLog, &key) {
+        state.toggle_log();
+    }
+    if pressed(state, Binding::ShowHelp, &key) {
+        state.toggle_help();
+    }
+    if pressed(state, Binding::ClearSearch, &key) && !state.search_mode {
         state.clear_search_string();
     }
+    if pressed(state, Binding::Undo, &key) {
+        state.undo().await;
+    }
+    if pressed(state, Binding::Sort, &key) {
+        state.cycle_sort_mode();
+    }
+    if pressed(state, Binding::Filter, &key) {
+        state.cycle_filter();
+    }
     match state.toggle_available_list {
         true => install_actions(key, state).await,
         false => update_and_delete_actions(key, state).await,
     }
 }
 
+fn config_actions(key: KeyEvent, state: &mut State) {
+    if pressed(state, Binding::Next, &key) {
+        state.next_config_file();
+    }
+    if pressed(state, Binding::Previous, &key) {
+        state.previous_config_file();
+    }
+    if pressed(state, Binding::ScrollDown, &key) {
+        state.scroll_config_down();
+    }
+    if pressed(state, Binding::ScrollUp, &key) {
+        state.scroll_config_up();
+    }
+    if pressed(state, Binding::EditConfig, &key) {
+        state.edit_config_file();
+    }
+    if pressed(state, Binding::NewConfig, &key) {
+        state.start_name_input(NameInput::Create);
+    }
+    if pressed(state, Binding::RenameConfig, &key)
+        && let Some(file) = state.selected_config_file().cloned()
+    {
+        state.start_name_input(NameInput::Rename(file));
+    }
+    if pressed(state, Binding::ShowLog, &key) {
+        state.toggle_log();
+    }
+    if pressed(state, Binding::ShowHelp, &key) {
+        state.toggle_help();
+    }
+}
+
 async fn install_actions(key: KeyEvent, state: &mut State) {
-    if let KeyCode::Char('j') = key.code {
+    if pressed(state, Binding::Next, &key) {
         state.next_available_plugin();
     }
-    if let KeyCode::Char('k') = key.code {
+    if pressed(state, Binding::Previous, &key) {
         state.previous_available_plugin();
     }
-    if let KeyCode::Char('I') = key.code {
-        state.install_plugin().await;
+    if pressed(state, Binding::Mark, &key) {
+        state.toggle_mark();
+    }
+    if pressed(state, Binding::MarkAll, &key) {
+        state.toggle_mark_all();
+    }
+    if pressed(state, Binding::Install, &key) {
+        let plugins = state.targets();
+        state.install_plugins(plugins).await;
+    }
+    if pressed(state, Binding::Preview, &key) {
+        state.preview_theme().await;
     }
 }
 
 async fn update_and_delete_actions(key: KeyEvent, state: &mut State) {
-    if let KeyCode::Char('j') = key.code {
+    if pressed(state, Binding::Next, &key) {
         state.next_installed_plugin();
     }
-    if let KeyCode::Char('k') = key.code {
+    if pressed(state, Binding::Previous, &key) {
         state.previous_installed_plugin();
     }
-    if let KeyCode::Char('U') = key.code {
-        state.update_plugin().await;
+    if pressed(state, Binding::Mark, &key) {
+        state.toggle_mark();
+    }
+    if pressed(state, Binding::MarkAll, &key) {
+        state.toggle_mark_all();
     }
-    if let KeyCode::Char('X') = key.code {
-        state.remove_plugin();
+    if pressed(state, Binding::Update, &key) {
+        let plugins = state.targets();
+        state.update_plugins(plugins).await;
+    }
+    if pressed(state, Binding::Disable, &key) {
+        let plugins = state.targets();
+        state.toggle_disabled(plugins).await;
+    }
+    if pressed(state, Binding::Delete, &key) {
+        let plugins = state.targets();
+        if !plugins.is_empty() {
+            state.request(PendingAction::Remove(plugins)).await;
+        }
+    }
+}
+
+fn contains(rect: Rect, mouse: &MouseEvent) -> bool {
+    rect.contains(Position::new(mouse.column, mouse.row))
+}
+
+pub async fn handle_mouse(mouse: MouseEvent, layout: &AppLayout, state: &mut State) {
+    if state.show_help {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => state.scroll_help_down(),
+            MouseEventKind::ScrollUp => state.scroll_help_up(),
+            _ => {}
+        }
+        return;
+    }
+    if state.show_log
+        || state.add_mode
+        || state.search_mode
+        || state.name_input.is_some()
+        || state.pending_action.is_some()
+    {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => handle_click(mouse, layout, state).await,
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            let down = mouse.kind == MouseEventKind::ScrollDown;
+            if contains(layout.config_files, &mouse) {
+                match down {
+                    true => state.next_config_file(),
+                    false => state.previous_config_file(),
+                }
+            } else if contains(layout.config_text, &mouse) {
+                match down {
+                    true => state.scroll_config_down(),
+                    false => state.scroll_config_up(),
+                }
+            } else if contains(layout.installed_list, &mouse) {
+                state.toggle_available_list = false;
+                match down {
+                    true => state.next_installed_plugin(),
+                    false => state.previous_installed_plugin(),
+                }
+            } else if layout.list.is_some_and(|list| contains(list, &mouse)) {
+                state.toggle_available_list = true;
+                match down {
+                    true => state.next_available_plugin(),
+                    false => state.previous_available_plugin(),
+                }
+            } else if contains(layout.readme, &mouse) {
+                match down {
+                    true => state.scroll_readme_down(),
+                    false => state.scroll_readme_up(),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn handle_click(mouse: MouseEvent, layout: &AppLayout, state: &mut State) {
+    let double = state.register_click(mouse.column, mouse.row);
+
+    if let Some(tab) = tab_at(layout.tabs, mouse.column, mouse.row)
+        .and_then(|index| WindowTab::tabs().into_iter().nth(index))
+    {
+        state.set_tab(tab);
+        state.reset_selected_available_plugin();
+        state.reset_selected_installed_plugin();
+    } else if contains(layout.config_files, &mouse) {
+        let row = list_row_at(layout.config_files, state.selected_config_index, mouse.row);
+        if row.is_some_and(|index| state.select_config_file(index)) && double {
+            state.edit_config_file();
+        }
+    } else if contains(layout.installed_list, &mouse) {
+        let row = list_row_at(
+            layout.installed_list,
+            state.selected_installed_plugin_index,
+            mouse.row,
+        );
+        if row.is_some_and(|index| state.select_installed_plugin(index)) && double {
+            let plugins = state.selected_plugin().into_iter().collect();
+            state.update_plugins(plugins).await;
+        }
+    } else if let Some(list) = layout.list
+        && contains(list, &mouse)
+    {
+        let row = list_row_at(list, state.selected_available_plugin_index, mouse.row);
+        if row.is_some_and(|index| state.select_available_plugin(index)) && double {
+            let plugins = state.selected_plugin().into_iter().collect();
+            state.install_plugins(plugins).await;
+        }
     }
 }
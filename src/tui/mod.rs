@@ -1,26 +1,55 @@
-use std::io;
+use std::{env, io, process::Command};
 
-use crossterm::event::{self};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
 use ratatui::{
     Frame, Terminal,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     prelude::Backend,
 };
 
 use crate::{
+    bindings::Binding,
+    log,
+    register::{Registry, TmuxPlugins, category_title},
     state::State,
     tui::{
-        input::handle_input, ui_banner::render_banner, ui_installed_list::render_installed_list,
-        ui_keymap::render_keymap, ui_list::render_list, ui_search_box::render_search_box,
+        input::{handle_input, handle_mouse},
+        ui_add_box::render_add_box,
+        ui_banner::render_banner,
+        ui_config::{render_config_files, render_config_text},
+        ui_confirm::render_confirm,
+        ui_details::render_details,
+        ui_help::{max_help_scroll, render_help},
+        ui_installed_list::render_installed_list,
+        ui_keymap::render_keymap,
+        ui_list::render_list,
+        ui_log::render_log,
+        ui_name_box::render_name_box,
+        ui_notifications::render_notifications,
+        ui_readme::render_readme,
+        ui_search_box::render_search_box,
         ui_tabs::render_tabs,
     },
 };
 
 mod input;
+mod ui_add_box;
 mod ui_banner;
+mod ui_config;
+mod ui_confirm;
+mod ui_details;
+mod ui_help;
 mod ui_installed_list;
 mod ui_keymap;
 mod ui_list;
+mod ui_log;
+mod ui_name_box;
+mod ui_notifications;
+mod ui_readme;
 mod ui_search_box;
 mod ui_tabs;
 
@@ -38,77 +67,232 @@ pub async fn run_tmuxedo_tui<B: Backend>(terminal: &mut Terminal<B>) -> io::Resu
             _ => {}
         }
 
+        state.sync_preview();
+        state.collect_reports();
+        state.sync_readme();
         terminal.draw(|f| {
             render(f, &state);
         })?;
-        if event::poll(std::time::Duration::from_millis(100))?
-            && let event::Event::Key(key) = event::read()?
-        {
-            if let event::KeyCode::Char('q') = key.code
-                && !state.search_mode
-            {
-                break;
+        let size = terminal.size()?;
+        state.help_max_scroll = max_help_scroll(Rect::new(0, 0, size.width, size.height));
+        if event::poll(std::time::Duration::from_millis(100))? {
+            match event::read()? {
+                Event::Key(key) => {
+                    if state.keymap.matches(Binding::Quit, &key)
+                        && !state.search_mode
+                        && !state.add_mode
+                        && state.name_input.is_none()
+                    {
+                        break;
+                    }
+                    handle_input(key, &mut state).await;
+                }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    let layout =
+                        AppLayout::new(Rect::new(0, 0, size.width, size.height), &state.tab);
+                    handle_mouse(mouse, &layout, &mut state).await;
+                }
+                _ => {}
             }
-            handle_input(key, &mut state).await;
+        }
+        if let Some(file) = state.pending_edit.take() {
+            let status = open_editor(terminal, &file);
+            state.finish_edit(file, status);
         }
     }
 
+    if let Some(preview) = state.preview.take()
+        && let Err(e) = preview.revert()
+    {
+        log::report(&format!("Failed to revert the theme preview: {e}"));
+    }
     Ok(())
 }
 
+// Hands the terminal to `$EDITOR` (falling back to `$VISUAL`, then vi) and
+// takes it back once the editor exits.
+fn open_editor<B: Backend>(
+    terminal: &mut Terminal<B>,
+    file: &std::path::Path,
+) -> io::Result<std::process::ExitStatus> {
+    let editor = env::var("EDITOR")
+        .or_else(|_| env::var("VISUAL"))
+        .unwrap_or_else(|_| String::from("vi"));
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(file)
+        .status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    status
+}
+
+// Shared by rendering and mouse handling, so clicks land on what was drawn.
+struct AppLayout {
+    banner: Rect,
+    tabs: Rect,
+    installed_list: Rect,
+    list: Option<Rect>,
+    details: Rect,
+    readme: Rect,
+    config_files: Rect,
+    config_text: Rect,
+    notifications: Rect,
+    keymap: Rect,
+}
+
+impl AppLayout {
+    fn new(area: Rect, tab: &WindowTab) -> Self {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(6),
+                    Constraint::Length(2),
+                    Constraint::Max(10),
+                    Constraint::Min(0),
+                    Constraint::Length(2),
+                    Constraint::Length(4),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        if *tab == WindowTab::Config {
+            let files_and_text = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(chunks[2].union(chunks[3]));
+            return Self {
+                banner: chunks[0],
+                tabs: chunks[1],
+                installed_list: Rect::default(),
+                list: None,
+                details: Rect::default(),
+                readme: Rect::default(),
+                config_files: files_and_text[0],
+                config_text: files_and_text[1],
+                notifications: chunks[4],
+                keymap: chunks[5],
+            };
+        }
+
+        let list_and_info = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[3]);
+        let details_and_readme = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(13), Constraint::Min(0)])
+            .split(list_and_info[1]);
+
+        Self {
+            banner: chunks[0],
+            tabs: chunks[1],
+            installed_list: chunks[2],
+            list: Some(list_and_info[0]),
+            details: details_and_readme[0],
+            readme: details_and_readme[1],
+            config_files: Rect::default(),
+            config_text: Rect::default(),
+            notifications: chunks[4],
+            keymap: chunks[5],
+        }
+    }
+}
+
 fn render(f: &mut Frame, state: &State) {
-    let size = f.area();
-
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Length(6),
-                Constraint::Length(2),
-                Constraint::Max(10),
-                Constraint::Min(0),
-                Constraint::Length(3),
-            ]
-            .as_ref(),
-        )
-        .split(size);
-
-    render_banner(f, chunks[0]);
-    render_tabs(f, chunks[1], state);
-    render_installed_list(f, chunks[2], state);
-    if state.tab != WindowTab::All {
-        render_list(f, chunks[3], state);
+    let layout = AppLayout::new(f.area(), &state.tab);
+
+    render_banner(f, layout.banner);
+    render_tabs(f, layout.tabs, state);
+    match state.tab {
+        WindowTab::Config => {
+            render_config_files(f, layout.config_files, state);
+            render_config_text(f, layout.config_text, state);
+        }
+        _ => {
+            render_installed_list(f, layout.installed_list, state);
+            if let Some(list) = layout.list {
+                render_list(f, list, state);
+            }
+            render_details(f, layout.details, state);
+            render_readme(f, layout.readme, state);
+        }
     }
-    render_keymap(f, chunks[4], state);
+    render_notifications(f, layout.notifications, state);
+    render_keymap(f, layout.keymap, state);
     if state.search_mode {
         render_search_box(f, state);
     }
+    if state.add_mode {
+        render_add_box(f, state);
+    }
+    if state.name_input.is_some() {
+        render_name_box(f, state);
+    }
+    if state.show_log {
+        render_log(f, state);
+    }
+    if state.show_help {
+        render_help(f, state);
+    }
+    if let Some(action) = &state.pending_action {
+        render_confirm(f, action);
+    }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub enum WindowTab {
     All,
-    Themes,
-    StatusBar,
-    Plugins,
+    Category(String),
+    Source(String),
+    Config,
 }
 
 impl WindowTab {
+    pub fn tabs() -> Vec<Self> {
+        let registry = Registry::get();
+        let mut tabs = vec![Self::All];
+        tabs.extend(registry.categories().iter().cloned().map(Self::Category));
+        tabs.extend(registry.sources().iter().cloned().map(Self::Source));
+        tabs.push(Self::Config);
+        tabs
+    }
+
     pub fn repr(&self) -> String {
-        match self {
-            Self::All => String::from("All (1)"),
-            Self::Themes => String::from("Themes (2)"),
-            Self::StatusBar => String::from("Status Bar (3)"),
-            Self::Plugins => String::from("Plugins (4)"),
+        let title = match self {
+            Self::All => String::from("All"),
+            Self::Category(category) => category_title(category),
+            Self::Source(name) => name.clone(),
+            Self::Config => String::from("Config"),
+        };
+        match self.index() {
+            index @ 0..=8 => format!("{title} ({})", index + 1),
+            _ => title,
         }
     }
 
     pub fn index(&self) -> usize {
+        Self::tabs()
+            .iter()
+            .position(|tab| tab == self)
+            .unwrap_or_default()
+    }
+
+    pub fn plugins(&self) -> Option<TmuxPlugins> {
         match self {
-            Self::All => 0,
-            Self::Themes => 1,
-            Self::StatusBar => 2,
-            Self::Plugins => 3,
+            Self::All => Some(TmuxPlugins::All),
+            Self::Config => None,
+            Self::Category(category) => Some(TmuxPlugins::Category(category.clone())),
+            Self::Source(name) => Some(TmuxPlugins::Source(name.clone())),
         }
     }
 }
@@ -0,0 +1,48 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::state::State;
+
+pub fn render_add_box(f: &mut Frame, state: &State) {
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .split(f.area());
+
+    let add_window = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(60),
+            Constraint::Min(0),
+        ])
+        .split(chunk[1]);
+
+    let mut block = Block::default()
+        .title(Span::from("Add plugin (owner/repo or URL, then branch)"))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .add_modifier(Modifier::BOLD);
+    if let Some(error) = &state.add_error {
+        block = block.title_bottom(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let text = Paragraph::new(state.add_string.clone())
+        .style(Style::default())
+        .block(block);
+
+    f.render_widget(Clear, add_window[1]);
+    f.render_widget(text, add_window[1]);
+}
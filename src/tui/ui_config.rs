@@ -0,0 +1,64 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{bindings::Binding, state::State, tmuxedo::config_file_name};
+
+pub fn render_config_files(f: &mut Frame, rect: Rect, state: &State) {
+    let top_bottom = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(rect);
+
+    let title = Paragraph::new("Config Files (sourcing order)")
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_config_index));
+
+    let list_items: Vec<ListItem> = state
+        .config_files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| ListItem::new(format!(" {}. {}", index + 1, config_file_name(file))))
+        .collect();
+
+    let list = List::new(list_items).highlight_style(Style::default().fg(Color::Yellow));
+    f.render_widget(title, top_bottom[0]);
+    f.render_stateful_widget(list, top_bottom[1], &mut list_state);
+}
+
+pub fn render_config_text(f: &mut Frame, rect: Rect, state: &State) {
+    let title = state
+        .selected_config_file()
+        .map(|file| file.display().to_string())
+        .unwrap_or_default();
+    let block = Block::default().title(title).borders(Borders::LEFT);
+
+    let lines: Vec<Line> = match &state.config_text {
+        Some(text) => text
+            .lines()
+            .map(|line| match line.trim_start().starts_with('#') {
+                true => Line::styled(line, Style::default().fg(Color::DarkGray)),
+                false => Line::from(line),
+            })
+            .collect(),
+        None if state.config_files.is_empty() => {
+            vec![Line::from(format!(
+                "No config files yet, press {} to create one",
+                state.keymap.repr(Binding::NewConfig)
+            ))]
+        }
+        None => vec![Line::from("Could not read this file")],
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((state.config_scroll, 0));
+
+    f.render_widget(paragraph, rect);
+}
@@ -0,0 +1,94 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::Span,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::state::PendingAction;
+
+const WIDTH: u16 = 60;
+
+// Rows a line takes once wrapped at word boundaries to `width` columns. Words
+// longer than a row are broken wherever they start.
+fn wrapped_height(line: &str, width: usize) -> usize {
+    let mut rows = 1;
+    let mut column = line.len() - line.trim_start().len();
+    for word in line.split_whitespace() {
+        let len = word.chars().count();
+        let gap = usize::from(column > 0);
+        match column > 0 && column + gap + len > width && len <= width {
+            true => {
+                rows += 1;
+                column = 0;
+            }
+            false => column += gap,
+        }
+        column += len;
+        while column > width {
+            rows += 1;
+            column -= width;
+        }
+    }
+    rows
+}
+
+// Sizes the dialog to the prompt. When the plugin list doesn't fit on
+// screen its tail is summed up, so the question and the warning stay visible.
+pub fn render_confirm(f: &mut Frame, action: &PendingAction) {
+    let inner_width = usize::from(WIDTH - 2);
+    let max_rows = usize::from(f.area().height.saturating_sub(2));
+    let height = |lines: &[String]| -> usize {
+        lines
+            .iter()
+            .map(|line| wrapped_height(line, inner_width))
+            .sum()
+    };
+
+    let mut lines = action.prompt();
+    if height(&lines) > max_rows && lines.len() > 2 {
+        let warning = lines.pop().unwrap_or_default();
+        let mut hidden = 0;
+        while lines.len() > 1 && height(&lines) + 2 > max_rows {
+            lines.pop();
+            hidden += 1;
+        }
+        lines.push(format!("  … and {hidden} more"));
+        lines.push(warning);
+    }
+    let rows = u16::try_from(height(&lines) + 2).unwrap_or(u16::MAX);
+
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(rows),
+            Constraint::Min(0),
+        ])
+        .split(f.area());
+
+    let confirm_window = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(WIDTH),
+            Constraint::Min(0),
+        ])
+        .split(chunk[1]);
+
+    let text = Paragraph::new(lines.join("\n"))
+        .style(Style::default())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(Span::from("Confirm"))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(Color::Red))
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(Clear, confirm_window[1]);
+    f.render_widget(text, confirm_window[1]);
+}
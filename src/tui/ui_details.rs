@@ -0,0 +1,85 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{register::Registry, state::State, utils::format_timestamp};
+
+fn format_date(seconds: u64) -> String {
+    let timestamp = format_timestamp(UNIX_EPOCH + Duration::from_secs(seconds));
+    timestamp[..10].to_string()
+}
+
+fn field<'a>(name: &'a str, value: String) -> Line<'a> {
+    Line::from(vec![
+        Span::styled(format!("{name}: "), Style::default().fg(Color::Yellow)),
+        Span::raw(value),
+    ])
+}
+
+pub fn render_details(f: &mut Frame, rect: Rect, state: &State) {
+    let block = Block::default().title("Details").borders(Borders::LEFT);
+
+    let selected = state.selected_plugin().unwrap_or_default();
+    let name = selected.split_whitespace().next().unwrap_or_default();
+
+    let lines = match Registry::get().find(name) {
+        Some(entry) => {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    entry.name.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(entry.description.clone()),
+                Line::from(""),
+                field("Tags", entry.tags.join(", ")),
+                field("Homepage", entry.homepage.clone()),
+                field("Source", entry.source.to_string()),
+            ];
+            if let Some(branch) = &entry.branch {
+                lines.push(field("Branch", branch.clone()));
+            }
+            if let Some(entry_point) = &entry.entry {
+                lines.push(field("Entry", entry_point.clone()));
+            }
+            if let Some(metadata) = state.registry_cache.get(&entry.name) {
+                lines.push(Line::from(""));
+                match metadata.reachable {
+                    true => {
+                        if let Some(last_commit) = metadata.last_commit {
+                            lines.push(field("Last commit", format_date(last_commit)));
+                        }
+                        if let Some(default_branch) = &metadata.default_branch {
+                            lines.push(field("Default branch", default_branch.clone()));
+                        }
+                        if metadata.archived == Some(true) {
+                            lines.push(field("Archived", String::from("yes")));
+                        }
+                    }
+                    false => lines.push(field("Remote", String::from("unreachable"))),
+                }
+                if let Some(refreshed) = state.registry_cache.refreshed {
+                    lines.push(field("Refreshed", format_date(refreshed)));
+                }
+            }
+            lines
+        }
+        None if !name.is_empty() => vec![
+            Line::from(Span::styled(
+                name.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from("Not in the registry"),
+        ],
+        None => vec![],
+    };
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, rect);
+}
@@ -0,0 +1,86 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::{bindings::HELP, state::State};
+
+const KEY_COLUMN_WIDTH: usize = 16;
+
+fn help_window(area: Rect) -> Rect {
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(60),
+            Constraint::Min(0),
+        ])
+        .split(chunk[1])[1]
+}
+
+// How far the help can scroll before its last line reaches the bottom of the
+// window: one line per binding and context title, with a blank line between
+// contexts.
+pub fn max_help_scroll(area: Rect) -> u16 {
+    let lines: usize = HELP
+        .iter()
+        .map(|(_, bindings)| bindings.len() + 1)
+        .sum::<usize>()
+        + HELP.len().saturating_sub(1);
+    let visible = help_window(area).height.saturating_sub(2);
+    u16::try_from(lines)
+        .unwrap_or(u16::MAX)
+        .saturating_sub(visible)
+}
+
+pub fn render_help(f: &mut Frame, state: &State) {
+    let help_window = help_window(f.area());
+
+    let mut lines = vec![];
+    for (context, bindings) in HELP {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            context,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for binding in bindings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<KEY_COLUMN_WIDTH$}", state.keymap.repr(*binding)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(binding.repr()),
+            ]));
+        }
+    }
+
+    let text = Paragraph::new(lines)
+        .style(Style::default())
+        .scroll((state.help_scroll.min(max_help_scroll(f.area())), 0))
+        .block(
+            Block::default()
+                .title(Span::from("Help"))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(Clear, help_window);
+    f.render_widget(text, help_window);
+}
@@ -2,10 +2,11 @@ use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{List, ListItem, ListState, Paragraph},
 };
 
-use crate::state::State;
+use crate::{state::State, tui::ui_list::highlight_matches};
 
 pub fn render_installed_list(f: &mut Frame, rect: Rect, state: &State) {
     let top_bottom = Layout::default()
@@ -13,10 +14,11 @@ pub fn render_installed_list(f: &mut Frame, rect: Rect, state: &State) {
         .constraints([Constraint::Length(1), Constraint::Min(0)])
         .split(rect);
 
+    let title = format!("Installed Plugins ({})", state.list_view());
     let title = if !state.toggle_available_list {
-        Paragraph::new("Installed Plugins").style(Style::default().add_modifier(Modifier::BOLD))
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD))
     } else {
-        Paragraph::new("Installed Plugins")
+        Paragraph::new(title)
     };
     let mut list_state = ListState::default();
     list_state.select(Some(state.selected_installed_plugin_index));
@@ -26,12 +28,23 @@ pub fn render_installed_list(f: &mut Frame, rect: Rect, state: &State) {
         .iter()
         .map(|s| {
             let p = &state.all_installed_plugins[s];
-            let display_line = match p.commit_hash.is_empty() {
-                true => format!(" * {}", s.clone()),
-                false => format!(" * {} - update available {}", s.clone(), p.commit_hash),
+            let bullet = match state.marked.contains(s) {
+                true => "✓",
+                false => "*",
             };
+            let mut spans = vec![Span::raw(format!(" {bullet} "))];
+            spans.extend(highlight_matches(s, &state.match_indices(s)));
+            if !p.commit_hash.is_empty() {
+                spans.push(Span::raw(format!(" - update available {}", p.commit_hash)));
+            }
+            if p.disabled {
+                spans.push(Span::raw(" (disabled)"));
+            }
+            if let Some(staleness) = state.stale_plugins.get(s) {
+                spans.push(Span::raw(format!(" ⚠ {staleness}")));
+            }
 
-            ListItem::new(display_line)
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
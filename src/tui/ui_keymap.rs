@@ -3,7 +3,7 @@ use ratatui::{
     layout::Rect,
     style::{Color, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Wrap},
 };
 
 use crate::{bindings, state::State};
@@ -16,7 +16,7 @@ pub fn render_keymap(f: &mut Frame, rect: Rect, state: &State) {
         .flat_map(|key| {
             vec![
                 Span::raw(" <"),
-                Span::styled(key.key(), Style::default().fg(Color::Yellow)),
+                Span::styled(state.keymap.repr(key), Style::default().fg(Color::Yellow)),
                 Span::raw(": "),
                 Span::raw(key.repr()),
                 Span::raw("> "),
@@ -27,7 +27,10 @@ pub fn render_keymap(f: &mut Frame, rect: Rect, state: &State) {
     let line = Line::from(line);
     let line = Text::from(vec![line]);
 
-    let paragraph = Paragraph::new(line).block(block).style(Style::default());
+    let paragraph = Paragraph::new(line)
+        .block(block)
+        .style(Style::default())
+        .wrap(Wrap { trim: true });
 
     f.render_widget(paragraph, rect);
 }
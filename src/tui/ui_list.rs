@@ -2,10 +2,57 @@ use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{List, ListItem, ListState, Paragraph},
 };
 
-use crate::state::State;
+use crate::{
+    register::{Registry, category_title},
+    state::State,
+    tui::WindowTab,
+};
+
+// Both plugin lists draw a title line above the rows, and a fresh `ListState`
+// only scrolls as far as needed to keep the selected row on screen.
+pub fn list_row_at(rect: Rect, selected: usize, row: u16) -> Option<usize> {
+    let top = rect.y + 1;
+    if row < top || row >= rect.y + rect.height {
+        return None;
+    }
+    let height = rect.height.saturating_sub(1) as usize;
+    let offset = (selected + 1).saturating_sub(height);
+    Some(offset + (row - top) as usize)
+}
+
+// Splits `plugin` into spans with the characters at `indices` highlighted.
+pub fn highlight_matches(plugin: &str, indices: &[usize]) -> Vec<Span<'static>> {
+    let highlight = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span> = vec![];
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    for (index, ch) in plugin.chars().enumerate() {
+        let matched = indices.contains(&index);
+        if matched != run_matched && !run.is_empty() {
+            let text = std::mem::take(&mut run);
+            spans.push(match run_matched {
+                true => Span::styled(text, highlight),
+                false => Span::raw(text),
+            });
+        }
+        run_matched = matched;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(match run_matched {
+            true => Span::styled(run, highlight),
+            false => Span::raw(run),
+        });
+    }
+    spans
+}
 
 pub fn render_list(f: &mut Frame, rect: Rect, state: &State) {
     let top_bottom = Layout::default()
@@ -13,10 +60,11 @@ pub fn render_list(f: &mut Frame, rect: Rect, state: &State) {
         .constraints([Constraint::Length(1), Constraint::Min(0)])
         .split(rect);
 
+    let title = format!("Available Plugins ({})", state.list_view());
     let title = if state.toggle_available_list {
-        Paragraph::new("Available Plugins").style(Style::default().add_modifier(Modifier::BOLD))
+        Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD))
     } else {
-        Paragraph::new("Available Plugins")
+        Paragraph::new(title)
     };
 
     let mut list_state = ListState::default();
@@ -25,7 +73,27 @@ pub fn render_list(f: &mut Frame, rect: Rect, state: &State) {
     let list_items: Vec<ListItem> = state
         .get_available_plugins()
         .iter()
-        .map(|s| ListItem::new(format!(" * {}", s.clone())))
+        .map(|s| {
+            let bullet = match state.marked.contains(s) {
+                true => "✓",
+                false => "*",
+            };
+            let mut spans = vec![Span::raw(format!(" {bullet} "))];
+            spans.extend(highlight_matches(s, &state.match_indices(s)));
+            if state.tab == WindowTab::All
+                && let Some(entry) = Registry::get().find(s)
+                && !entry.category.is_empty()
+            {
+                spans.push(Span::styled(
+                    format!(" [{}]", category_title(&entry.category)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if state.preview.as_ref().is_some_and(|p| p.plugin == *s) {
+                spans.push(Span::raw(" (previewing)"));
+            }
+            ListItem::new(Line::from(spans))
+        })
         .collect();
 
     let list = if state.toggle_available_list {
@@ -0,0 +1,50 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::state::State;
+
+pub fn render_log(f: &mut Frame, state: &State) {
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ])
+        .split(f.area());
+
+    let log_window = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(5),
+            Constraint::Percentage(90),
+            Constraint::Percentage(5),
+        ])
+        .split(chunk[1]);
+
+    let height = log_window[1].height.saturating_sub(2) as usize;
+    let skip = state.log_entries.len().saturating_sub(height);
+    let lines: Vec<Line> = state.log_entries[skip..]
+        .iter()
+        .map(|entry| match entry.contains(" ERROR ") {
+            true => Line::from(Span::styled(entry.clone(), Style::default().fg(Color::Red))),
+            false => Line::from(entry.clone()),
+        })
+        .collect();
+
+    let text = Paragraph::new(lines).style(Style::default()).block(
+        Block::default()
+            .title(Span::from("Log"))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    f.render_widget(Clear, log_window[1]);
+    f.render_widget(text, log_window[1]);
+}
@@ -0,0 +1,55 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    state::{NameInput, State},
+    tmuxedo::config_file_name,
+};
+
+pub fn render_name_box(f: &mut Frame, state: &State) {
+    let chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .split(f.area());
+
+    let name_window = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(60),
+            Constraint::Min(0),
+        ])
+        .split(chunk[1]);
+
+    let title = match &state.name_input {
+        Some(NameInput::Rename(file)) => format!("Rename {}", config_file_name(file)),
+        _ => String::from("New config file"),
+    };
+    let mut block = Block::default()
+        .title(Span::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .add_modifier(Modifier::BOLD);
+    if let Some(error) = &state.name_error {
+        block = block.title_bottom(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let text = Paragraph::new(state.name_string.clone())
+        .style(Style::default())
+        .block(block);
+
+    f.render_widget(Clear, name_window[1]);
+    f.render_widget(text, name_window[1]);
+}
@@ -0,0 +1,31 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::state::State;
+
+pub fn render_notifications(f: &mut Frame, rect: Rect, state: &State) {
+    let notifications: Vec<_> = state.recent_notifications().collect();
+    let skip = notifications.len().saturating_sub(rect.height as usize);
+
+    let lines: Vec<Line> = notifications[skip..]
+        .iter()
+        .map(|notification| {
+            let (symbol, color) = match notification.success {
+                true => ("✔", Color::Green),
+                false => ("✘", Color::Red),
+            };
+            let message = notification.message.trim().replace('\n', " | ");
+            Line::from(Span::styled(
+                format!(" {symbol} {message}"),
+                Style::default().fg(color),
+            ))
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines), rect);
+}
@@ -0,0 +1,123 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use regex::Regex;
+
+use crate::state::State;
+
+// Renders the subset of markdown READMEs lean on: headings, lists, code
+// blocks and inline code/bold/links. Images and raw HTML are dropped.
+fn render_markdown(text: &str) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(Span::styled(
+                format!("  {line}"),
+                Style::default().fg(Color::Green),
+            )));
+            continue;
+        }
+        if trimmed.starts_with('<') || trimmed.starts_with("![") {
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            lines.push(Line::from(Span::styled(
+                heading.to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        match trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            Some(item) => {
+                let mut spans = vec![Span::raw(format!("{indent}• "))];
+                spans.extend(render_inline(item));
+                lines.push(Line::from(spans));
+            }
+            None => lines.push(Line::from(render_inline(line))),
+        }
+    }
+    lines
+}
+
+fn render_inline(text: &str) -> Vec<Span<'static>> {
+    let re = Regex::new(r"`([^`]+)`|\*\*([^*]+)\*\*|!?\[([^\]]*)\]\([^)]*\)").unwrap();
+    let mut spans = vec![];
+    let mut last = 0;
+
+    for caps in re.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        spans.push(Span::raw(text[last..whole.start()].to_string()));
+        last = whole.end();
+
+        if let Some(code) = caps.get(1) {
+            spans.push(Span::styled(
+                code.as_str().to_string(),
+                Style::default().fg(Color::Green),
+            ));
+        } else if let Some(bold) = caps.get(2) {
+            spans.push(Span::styled(
+                bold.as_str().to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        } else if let Some(link) = caps.get(3)
+            && !whole.as_str().starts_with('!')
+        {
+            spans.push(Span::styled(
+                link.as_str().to_string(),
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            ));
+        }
+    }
+    spans.push(Span::raw(text[last..].to_string()));
+    spans
+}
+
+pub fn render_readme(f: &mut Frame, rect: Rect, state: &State) {
+    let block = Block::default().title("README").borders(Borders::LEFT);
+
+    let mut lines = vec![];
+    if !state.readme.options.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Options: ", Style::default().fg(Color::Yellow)),
+            Span::raw(state.readme.options.join(", ")),
+        ]));
+        lines.push(Line::from(""));
+    }
+    match &state.readme.text {
+        Some(text) => lines.extend(render_markdown(text)),
+        None if !state.readme.plugin.is_empty() => {
+            lines.push(Line::from(match state.readme.installed {
+                true => "No README found",
+                false => "No README cached, run `tmuxedo registry refresh`",
+            }))
+        }
+        None => {}
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((state.readme_scroll, 0));
+
+    f.render_widget(paragraph, rect);
+}
@@ -3,7 +3,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Modifier, Style, Stylize},
     text::Span,
-    widgets::{Block, BorderType, Borders, Paragraph},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
 };
 
 use crate::state::State;
@@ -37,5 +37,6 @@ pub fn render_search_box(f: &mut Frame, state: &State) {
                 .add_modifier(Modifier::BOLD),
         );
 
+    f.render_widget(Clear, search_window[1]);
     f.render_widget(text, search_window[1]);
 }
@@ -8,25 +8,39 @@ use ratatui::{
 
 use crate::{state::State, tui::WindowTab};
 
-pub fn render_tabs(f: &mut Frame, rect: Rect, state: &State) {
-    let tab_titles = [
-        WindowTab::All.repr(),
-        WindowTab::Themes.repr(),
-        WindowTab::StatusBar.repr(),
-        WindowTab::Plugins.repr(),
-    ];
+const PADDING: &str = " ";
+const DIVIDER: &str = " | ";
 
-    let titles: Vec<Line> = tab_titles
+fn titles() -> Vec<Line<'static>> {
+    WindowTab::tabs()
         .iter()
-        .map(|t| {
+        .map(|tab| {
             Line::from(Span::styled(
-                format!(" {t} "),
+                format!(" {} ", tab.repr()),
                 Style::default().fg(Color::White),
             ))
         })
-        .collect();
+        .collect()
+}
 
-    let tabs = Tabs::new(titles)
+// Mirrors how `Tabs` lays out its titles: padding, title, padding, divider.
+pub fn tab_at(rect: Rect, column: u16, row: u16) -> Option<usize> {
+    if row != rect.y {
+        return None;
+    }
+    let mut x = rect.x;
+    for (index, title) in titles().iter().enumerate() {
+        let width = (PADDING.len() * 2 + title.width()) as u16;
+        if (x..x + width).contains(&column) {
+            return Some(index);
+        }
+        x += width + DIVIDER.len() as u16;
+    }
+    None
+}
+
+pub fn render_tabs(f: &mut Frame, rect: Rect, state: &State) {
+    let tabs = Tabs::new(titles())
         .block(Block::default().borders(Borders::BOTTOM))
         .highlight_style(
             Style::default()
@@ -34,7 +48,8 @@ pub fn render_tabs(f: &mut Frame, rect: Rect, state: &State) {
                 .add_modifier(Modifier::BOLD),
         )
         .select(state.tab.index())
-        .divider(Span::raw(" | "));
+        .padding(PADDING, PADDING)
+        .divider(Span::raw(DIVIDER));
 
     f.render_widget(tabs, rect);
 }
@@ -1,3 +1,70 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// `owner/repo.git` is the GitHub shorthand with a stray suffix, so `.git` only
+// marks a URL or a local repository path in any other form.
+pub fn is_git_url(value: &str) -> bool {
+    value.contains("://")
+        || value.starts_with("git@")
+        || (value.ends_with(".git") && !is_github_shorthand(value))
+}
+
+fn is_github_shorthand(value: &str) -> bool {
+    let mut parts = value.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(repo), None) => {
+            !owner.is_empty()
+                && !repo.is_empty()
+                && !owner.starts_with('.')
+                && !owner.starts_with('~')
+        }
+        _ => false,
+    }
+}
+
+// Drops the `.git` suffix from `owner/repo.git` so that it names the same
+// plugin as `owner/repo`.
+pub fn normalize_plugin(value: &str) -> &str {
+    match is_git_url(value) {
+        true => value,
+        false => value.strip_suffix(".git").unwrap_or(value),
+    }
+}
+
+// URLs are named after their last two path segments, so
+// `https://gitlab.com/owner/repo.git` is cloned into `owner_repo` like `owner/repo` is.
 pub fn format_plugin_dir_name(dir: &str) -> String {
-    dir.replace("/", "_")
+    if !is_git_url(dir) {
+        return dir.replace("/", "_");
+    }
+    let name = dir.trim_end_matches('/').trim_end_matches(".git");
+    let mut segments: Vec<&str> = name.rsplit([':', '/']).take(2).collect();
+    segments.reverse();
+    segments.join("_")
+}
+
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+
+    // Civil-from-days, see https://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
 }